@@ -0,0 +1,122 @@
+//! Content embedded with `w:altChunk`, e.g. an HTML fragment or another docx.
+
+use crate::package::{content_type, Package};
+use roxmltree::Node;
+
+/// What an embedded chunk contains.
+pub enum AltChunk<'a> {
+    /// Text paragraphs, from HTML or plain text
+    Paragraphs(Vec<String>),
+    /// A complete docx package
+    Document(&'a [u8]),
+    /// A chunk that can't be converted, with its content type
+    Unsupported(String),
+}
+
+impl<'a> AltChunk<'a> {
+    pub fn from_part(
+        package: &'a Package,
+        content_types: Option<Node>,
+        part: &str,
+    ) -> Option<Self> {
+        let data = package.bytes(part)?;
+        let content_type = content_type(content_types, part).unwrap_or_default();
+        let chunk = match content_type.as_str() {
+            "text/html" | "application/xhtml+xml" => {
+                AltChunk::Paragraphs(html_paragraphs(&String::from_utf8_lossy(data)))
+            }
+            "text/plain" => AltChunk::Paragraphs(
+                String::from_utf8_lossy(data)
+                    .lines()
+                    .map(|line| line.trim().to_string())
+                    .filter(|line| !line.is_empty())
+                    .collect(),
+            ),
+            "application/vnd.openxmlformats-officedocument.wordprocessingml.document"
+            | "application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml" => {
+                AltChunk::Document(data)
+            }
+            _ => AltChunk::Unsupported(content_type),
+        };
+        Some(chunk)
+    }
+}
+
+/// Elements that start a new paragraph in HTML.
+const HTML_BLOCKS: [&str; 17] = [
+    "p",
+    "div",
+    "br",
+    "li",
+    "tr",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "table",
+    "ul",
+    "ol",
+    "blockquote",
+    "pre",
+    "hr",
+];
+
+/// The text of an HTML fragment, split into paragraphs at block elements.
+fn html_paragraphs(html: &str) -> Vec<String> {
+    let mut paragraphs = vec![];
+    let mut text = String::new();
+    let mut skip_until: Option<String> = None;
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        if skip_until.is_none() {
+            text += &rest[..start];
+        }
+        let Some(end) = rest[start..].find('>') else {
+            break;
+        };
+        let tag = &rest[start + 1..start + end];
+        rest = &rest[start + end + 1..];
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if let Some(until) = &skip_until {
+            if tag.starts_with('/') && &name == until {
+                skip_until = None;
+            }
+            continue;
+        }
+        if (name == "script" || name == "style" || name == "head") && !tag.starts_with('/') {
+            skip_until = Some(name);
+        } else if HTML_BLOCKS.contains(&name.as_str()) {
+            push_paragraph(&mut paragraphs, &mut text);
+        }
+    }
+    if skip_until.is_none() {
+        text += rest;
+    }
+    push_paragraph(&mut paragraphs, &mut text);
+    paragraphs
+}
+
+fn push_paragraph(paragraphs: &mut Vec<String>, text: &mut String) {
+    let paragraph = decode_entities(&text.split_whitespace().collect::<Vec<_>>().join(" "));
+    if !paragraph.is_empty() {
+        paragraphs.push(paragraph);
+    }
+    text.clear();
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
@@ -0,0 +1,141 @@
+//! Charts, whose data is stored in a separate chart part of the package.
+
+use crate::package::{is_element, A_NS};
+use crate::{
+    MarkdownParagraph, MarkdownTable, MarkdownTableCell, MarkdownTableRow, TextBlock, TextType,
+};
+use roxmltree::Node;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+pub const C_NS: &str = "http://schemas.openxmlformats.org/drawingml/2006/chart";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkdownChart {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// The kind of chart, e.g. `barChart` or `pieChart`
+    pub chart_type: String,
+    /// The chart data: a header row with the series names, followed by a row per category
+    pub table: MarkdownTable,
+}
+
+impl MarkdownChart {
+    /// Parse a chart part, e.g. `word/charts/chart1.xml`.
+    pub fn from_xml(xml: &str) -> Option<Self> {
+        let document = roxmltree::Document::parse(xml).ok()?;
+        let chart = child(document.root_element(), "chart")?;
+        let title = child(chart, "title")
+            .map(|title| {
+                title
+                    .descendants()
+                    .filter(|node| is_element(node, A_NS, "t"))
+                    .filter_map(|node| node.text())
+                    .collect::<String>()
+            })
+            .filter(|title| !title.is_empty());
+        let plot = child(chart, "plotArea")?.children().find(|node| {
+            node.is_element()
+                && node.tag_name().namespace() == Some(C_NS)
+                && node.tag_name().name().ends_with("Chart")
+        })?;
+
+        let mut categories: Vec<String> = vec![];
+        let mut series: Vec<(String, Vec<(usize, String)>)> = vec![];
+        for ser in plot.children().filter(|node| is_element(node, C_NS, "ser")) {
+            let name = child(ser, "tx")
+                .map(|tx| {
+                    let names: Vec<String> =
+                        cached_points(tx).into_iter().map(|(_, v)| v).collect();
+                    names.join(" ")
+                })
+                .unwrap_or_default();
+            if let Some(cat) = child(ser, "cat").or_else(|| child(ser, "xVal")) {
+                for (idx, value) in cached_points(cat) {
+                    if idx >= categories.len() {
+                        categories.resize(idx + 1, "".to_string());
+                    }
+                    categories[idx] = value;
+                }
+            }
+            let values = child(ser, "val")
+                .or_else(|| child(ser, "yVal"))
+                .map(cached_points)
+                .unwrap_or_default();
+            series.push((name, values));
+        }
+
+        let row_count = series
+            .iter()
+            .flat_map(|(_, values)| values.iter().map(|(idx, _)| idx + 1))
+            .chain([categories.len()])
+            .max()
+            .unwrap_or(0);
+        let mut table: MarkdownTable = vec![MarkdownTableRow {
+            is_header: true,
+            cells: [String::new()]
+                .into_iter()
+                .chain(series.iter().map(|(name, _)| name.clone()))
+                .map(text_cell)
+                .collect(),
+            continued: vec![],
+            backgrounds: BTreeMap::new(),
+        }];
+        for row in 0..row_count {
+            let category = match categories.get(row) {
+                Some(category) => category.clone(),
+                None => (row + 1).to_string(),
+            };
+            let values = series.iter().map(|(_, values)| {
+                values
+                    .iter()
+                    .find(|(idx, _)| *idx == row)
+                    .map(|(_, value)| value.clone())
+                    .unwrap_or_default()
+            });
+            table.push(MarkdownTableRow {
+                is_header: false,
+                cells: [category]
+                    .into_iter()
+                    .chain(values)
+                    .map(text_cell)
+                    .collect(),
+                continued: vec![],
+                backgrounds: BTreeMap::new(),
+            });
+        }
+
+        Some(MarkdownChart {
+            title,
+            chart_type: plot.tag_name().name().to_string(),
+            table,
+        })
+    }
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|child| is_element(child, C_NS, name))
+}
+
+/// The cached values (`c:pt`) of a series name, category or value reference, with their index.
+fn cached_points(node: Node) -> Vec<(usize, String)> {
+    node.descendants()
+        .filter(|descendant| is_element(descendant, C_NS, "pt"))
+        .filter_map(|pt| {
+            let idx = pt.attribute("idx")?.parse().ok()?;
+            let value = child(pt, "v")?.text()?.to_string();
+            Some((idx, value))
+        })
+        .collect()
+}
+
+fn text_cell(text: String) -> MarkdownTableCell {
+    let mut paragraph = MarkdownParagraph::new();
+    if !text.is_empty() {
+        paragraph
+            .blocks
+            .push(TextBlock::new(text, None, TextType::Text));
+    }
+    MarkdownTableCell::new(vec![paragraph])
+}
@@ -0,0 +1,115 @@
+//! Comments (`w:comment`) with the text they were left on, e.g. for review tooling.
+
+use crate::package::{is_element, Package, W_NS};
+use roxmltree::Node;
+use serde::Serialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkdownComment {
+    /// The id of the comment in the comments part
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// The date as written in the document, e.g. `2014-06-25T10:40:00Z`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    /// The commented text, from `w:commentRangeStart` to `w:commentRangeEnd`, with a line per
+    /// paragraph
+    pub range_text: String,
+    /// The text of the comment, with a line per paragraph
+    pub text: String,
+}
+
+impl MarkdownComment {
+    /// The comment as shown in the markdown, e.g. `comment by Ada: Check this`.
+    pub(crate) fn annotation(&self) -> String {
+        match &self.author {
+            Some(author) => format!("comment by {author}: {}", self.text),
+            None => format!("comment: {}", self.text),
+        }
+    }
+}
+
+/// Collect the comments of `word/comments.xml`, in the order of the part, with the text of
+/// `body` that they are anchored to.
+pub(crate) fn parse_comments(package: &Package, body: Option<Node>) -> Vec<MarkdownComment> {
+    let Some(comments) = package
+        .xml("word/comments.xml")
+        .and_then(|xml| roxmltree::Document::parse(xml).ok())
+    else {
+        return vec![];
+    };
+    let mut ranges = body.map(comment_ranges).unwrap_or_default();
+    comments
+        .root_element()
+        .children()
+        .filter(|node| is_element(node, W_NS, "comment"))
+        .map(|comment| {
+            let id = comment.attribute((W_NS, "id")).unwrap_or_default();
+            MarkdownComment {
+                id: id.to_string(),
+                author: comment
+                    .attribute((W_NS, "author"))
+                    .map(|author| author.to_string()),
+                date: comment
+                    .attribute((W_NS, "date"))
+                    .map(|date| date.to_string()),
+                range_text: ranges
+                    .remove(id)
+                    .map(|text| text.trim().to_string())
+                    .unwrap_or_default(),
+                text: comment_text(comment),
+            }
+        })
+        .collect()
+}
+
+/// The text of a comment, with a line per paragraph.
+pub(crate) fn comment_text(comment: Node) -> String {
+    let paragraphs: Vec<String> = comment
+        .children()
+        .filter(|node| is_element(node, W_NS, "p"))
+        .map(|paragraph| {
+            paragraph
+                .descendants()
+                .filter(|node| is_element(node, W_NS, "t"))
+                .filter_map(|node| node.text())
+                .collect()
+        })
+        .collect();
+    paragraphs.join("\n")
+}
+
+/// The commented text of the body, keyed by comment id. A range without an end runs to the end
+/// of the body.
+fn comment_ranges(body: Node) -> HashMap<String, String> {
+    let mut ranges: HashMap<String, String> = HashMap::new();
+    let mut open: Vec<&str> = vec![];
+    for node in body.descendants() {
+        if is_element(&node, W_NS, "commentRangeStart") {
+            if let Some(id) = node.attribute((W_NS, "id")) {
+                open.push(id);
+                ranges.entry(id.to_string()).or_default();
+            }
+        } else if is_element(&node, W_NS, "commentRangeEnd") {
+            open.retain(|id| node.attribute((W_NS, "id")) != Some(id));
+        } else if is_element(&node, W_NS, "p") {
+            for id in &open {
+                let text = ranges.entry(id.to_string()).or_default();
+                if !text.is_empty() {
+                    text.push('\n');
+                }
+            }
+        } else if is_element(&node, W_NS, "t") {
+            for id in &open {
+                ranges
+                    .entry(id.to_string())
+                    .or_default()
+                    .push_str(node.text().unwrap_or_default());
+            }
+        }
+    }
+    ranges
+}
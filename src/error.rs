@@ -0,0 +1,55 @@
+//! Why a docx file couldn't be parsed.
+
+use std::fmt;
+use std::io;
+use zip::result::ZipError;
+
+#[derive(Debug)]
+pub enum DocxParseError {
+    /// The file couldn't be read, e.g. because it doesn't exist
+    Io(io::Error),
+    /// The file is not a valid zip file
+    Zip(ZipError),
+    /// The XML of a part is malformed, or isn't what docx-rust expects
+    Xml(String),
+    /// A part that every docx has is missing, e.g. `word/document.xml`
+    MissingPart(String),
+    /// The document can't be converted, e.g. because a part is UTF-16 or, with
+    /// [`MarkdownOptions::strict`](crate::MarkdownOptions::strict), because content would be
+    /// dropped
+    Unsupported(String),
+}
+
+impl fmt::Display for DocxParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DocxParseError::Io(error) => write!(f, "{error}"),
+            DocxParseError::Zip(error) => write!(f, "invalid zip file: {error}"),
+            DocxParseError::Xml(message) => write!(f, "invalid XML: {message}"),
+            DocxParseError::MissingPart(name) => write!(f, "missing part {name}"),
+            DocxParseError::Unsupported(message) => write!(f, "unsupported document: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for DocxParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DocxParseError::Io(error) => Some(error),
+            DocxParseError::Zip(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for DocxParseError {
+    fn from(error: io::Error) -> Self {
+        DocxParseError::Io(error)
+    }
+}
+
+impl From<ZipError> for DocxParseError {
+    fn from(error: ZipError) -> Self {
+        DocxParseError::Zip(error)
+    }
+}
@@ -0,0 +1,104 @@
+//! Fields that refer to a bookmark (`REF` / `PAGEREF`), e.g. a "see Table 1" cross-reference,
+//! and page numbers (`PAGE`), e.g. in a footer.
+
+use crate::package::{is_element, W_NS};
+use roxmltree::Node;
+
+/// A complex field (`w:fldChar`) that refers to a bookmark, by the runs of a paragraph.
+#[derive(Debug, PartialEq)]
+pub struct ReferenceField {
+    /// The index of the first run of the field result
+    pub start: usize,
+    /// The index after the last run of the field result
+    pub end: usize,
+    pub bookmark: String,
+}
+
+/// The bookmark that a field instruction refers to, e.g. `_Ref1` for ` REF _Ref1 \h `.
+pub fn reference_target(instruction: &str) -> Option<&str> {
+    let mut words = instruction.split_whitespace();
+    match words.next()? {
+        "REF" | "PAGEREF" => words.next(),
+        _ => None,
+    }
+}
+
+/// Whether a field instruction is the current page number, e.g. ` PAGE \* MERGEFORMAT `.
+pub fn is_page_number(instruction: &str) -> bool {
+    instruction.split_whitespace().next() == Some("PAGE")
+}
+
+/// Find the reference fields among the runs of a paragraph. Fields with an empty result are left
+/// out.
+pub fn reference_fields(runs: &[Node]) -> Vec<ReferenceField> {
+    complex_fields(runs)
+        .into_iter()
+        .filter(|(start, end, _)| start < end)
+        .filter_map(|(start, end, instruction)| {
+            Some(ReferenceField {
+                start,
+                end,
+                bookmark: reference_target(&instruction)?.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Find the page number fields among the runs of a paragraph, by the index of their first result
+/// run and the index after their last one. A field without a result starts and ends at the run
+/// that ends it.
+pub fn page_number_fields(runs: &[Node]) -> Vec<(usize, usize)> {
+    complex_fields(runs)
+        .into_iter()
+        .filter(|(_, _, instruction)| is_page_number(instruction))
+        .map(|(start, end, _)| (start, end))
+        .collect()
+}
+
+/// The outermost complex fields among the runs of a paragraph: the index of the first result run,
+/// the index after the last one, and the instruction.
+fn complex_fields(runs: &[Node]) -> Vec<(usize, usize, String)> {
+    let mut fields = vec![];
+    let mut instruction = String::new();
+    // The index of the first result run, once the field is separated from its instruction.
+    let mut start: Option<usize> = None;
+    let mut depth = 0;
+    for (i, run) in runs.iter().enumerate() {
+        for child in run.children() {
+            if is_element(&child, W_NS, "instrText") && depth == 1 && start.is_none() {
+                instruction.push_str(child.text().unwrap_or_default());
+            } else if is_element(&child, W_NS, "fldChar") {
+                match child.attribute((W_NS, "fldCharType")) {
+                    Some("begin") => {
+                        depth += 1;
+                        if depth == 1 {
+                            instruction.clear();
+                            start = None;
+                        }
+                    }
+                    Some("separate") if depth == 1 => start = Some(i + 1),
+                    Some("end") => {
+                        depth -= 1;
+                        if depth > 0 {
+                            continue;
+                        }
+                        depth = 0;
+                        let start = start.take().unwrap_or(i).min(i);
+                        fields.push((start, i, instruction.clone()));
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+    fields
+}
+
+#[test]
+fn test_reference_target() {
+    assert_eq!(reference_target(" REF _Ref1 \\h "), Some("_Ref1"));
+    assert_eq!(reference_target("PAGEREF _Ref2 \\h"), Some("_Ref2"));
+    assert_eq!(reference_target(" SEQ Table \\* ARABIC "), None);
+    assert!(is_page_number(" PAGE \\* MERGEFORMAT "));
+    assert!(!is_page_number(" PAGEREF _Ref2 \\h"));
+}
@@ -0,0 +1,90 @@
+//! Page headers and footers (`w:hdr` / `w:ftr`), which are kept in separate parts of the package
+//! and referenced by the section properties.
+
+use crate::package::{is_element, outer_xml, part_path, Package, R_NS, W_NS};
+use crate::{MarkdownParagraph, ParseContext, PlainTextOptions, TextBlock, TextType};
+use hard_xml::XmlRead;
+use roxmltree::Node;
+
+/// The headers and footers that the section properties of `body` refer to, in document order,
+/// with a paragraph per part. The paragraphs of a part are joined by line breaks, and parts
+/// without text are left out.
+pub(crate) fn parse_headers_footers(
+    package: &Package,
+    body: Node,
+    context: &ParseContext,
+) -> (Vec<MarkdownParagraph>, Vec<MarkdownParagraph>) {
+    let mut targets: Vec<(&str, &str)> = vec![];
+    let references = body
+        .descendants()
+        .filter(|node| is_element(node, W_NS, "sectPr"))
+        .flat_map(|section| section.children());
+    for reference in references {
+        let kind = if is_element(&reference, W_NS, "headerReference") {
+            "header"
+        } else if is_element(&reference, W_NS, "footerReference") {
+            "footer"
+        } else {
+            continue;
+        };
+        let Some(target) = reference
+            .attribute((R_NS, "id"))
+            .and_then(|id| context.target(id))
+        else {
+            continue;
+        };
+        if !targets.contains(&(kind, target)) {
+            targets.push((kind, target));
+        }
+    }
+
+    let (mut headers, mut footers) = (vec![], vec![]);
+    for (kind, target) in targets {
+        let Some(paragraph) = parse_part(package, &part_path(target), context) else {
+            continue;
+        };
+        match kind {
+            "header" => headers.push(paragraph),
+            _ => footers.push(paragraph),
+        }
+    }
+    (headers, footers)
+}
+
+/// The paragraphs of a header or footer part as a single paragraph.
+fn parse_part(package: &Package, path: &str, context: &ParseContext) -> Option<MarkdownParagraph> {
+    let part = roxmltree::Document::parse(package.xml(path)?).ok()?;
+    let mut paragraphs = part
+        .root_element()
+        .descendants()
+        // Paragraphs in a text box are part of the paragraph that anchors it.
+        .filter(|node| {
+            is_element(node, W_NS, "p")
+                && !node
+                    .ancestors()
+                    .skip(1)
+                    .any(|ancestor| is_element(&ancestor, W_NS, "p"))
+        })
+        .filter_map(|raw| {
+            let paragraph = docx_rust::document::Paragraph::from_str(outer_xml(raw)).ok()?;
+            Some(MarkdownParagraph::from_paragraph(
+                &paragraph,
+                Some(raw),
+                context,
+            ))
+        })
+        .filter(|paragraph| {
+            !paragraph
+                .to_plain_text(&PlainTextOptions::default())
+                .trim()
+                .is_empty()
+        });
+    let mut joined = paragraphs.next()?;
+    for paragraph in paragraphs {
+        joined
+            .blocks
+            .push(TextBlock::new(String::new(), None, TextType::LineBreak));
+        joined.blocks.extend(paragraph.blocks);
+    }
+    Some(joined)
+}
@@ -0,0 +1,314 @@
+//! Conversion to HTML, e.g. for a web viewer, without going through markdown.
+
+use crate::utils::{highlight_rgb, link_target, link_text};
+use crate::{
+    HtmlOptions, MarkdownContent, MarkdownDocument, MarkdownParagraph, MarkdownTable,
+    ParagraphStyle, SmartArtNode, TextType, VMergeKind,
+};
+
+impl MarkdownDocument {
+    /// Convert the document to HTML: headings are `<h1>` to `<h6>`, lists are `<ul>` and `<ol>`,
+    /// tables keep their merged cells, and formatting is `<strong>`, `<em>`, `<u>`, `<s>`,
+    /// `<sup>`, `<sub>` and colored `<span>`s. The headings, excluded styles and images follow
+    /// [`HtmlOptions::markdown`], like [`MarkdownDocument::to_markdown_with`].
+    pub fn to_html(&self, options: &HtmlOptions) -> String {
+        let markdown_options = &options.markdown;
+        let mut html = String::new();
+        if let Some(title) = &self.title {
+            html += &format!("<h1>{}</h1>\n", escape(title));
+        }
+
+        // The tags of the open lists, by level, each with an open item.
+        let mut lists: Vec<&str> = vec![];
+        for content in &self.content {
+            let MarkdownContent::Paragraph(paragraph) = content else {
+                close_lists(&mut html, &mut lists, 0);
+                match content {
+                    MarkdownContent::Table(table) => html += &self.table_html(table, options),
+                    MarkdownContent::Chart(chart) => {
+                        let name = chart.title.as_ref().unwrap_or(&chart.chart_type);
+                        html += &format!("<p><em>Chart: {}</em></p>\n", escape(name));
+                        html += &self.table_html(&chart.table, options);
+                    }
+                    MarkdownContent::SmartArt(smart_art) => {
+                        html += &smart_art_html(&smart_art.nodes);
+                    }
+                    MarkdownContent::Paragraph(_) => (),
+                }
+                continue;
+            };
+            if paragraph.is_excluded(markdown_options) {
+                continue;
+            }
+            let style = paragraph.resolved_style(&self.styles);
+            let inline = self.paragraph_html(paragraph, &style, options);
+            let list_item = style
+                .numbering
+                .as_ref()
+                .and_then(|numbering| Some((numbering.id?, numbering.indent_level)));
+            match (
+                self.paragraph_heading_level(paragraph, markdown_options),
+                list_item,
+            ) {
+                (Some(level), _) if !inline.is_empty() => {
+                    close_lists(&mut html, &mut lists, 0);
+                    let level = level.clamp(0, 5) + 1;
+                    html += &format!("<h{level}>{inline}</h{level}>\n");
+                }
+                (None, Some((id, level))) => {
+                    let level = level.unwrap_or(0).max(0) as usize;
+                    let numbering_level = self
+                        .numberings
+                        .get(&id)
+                        .and_then(|numbering| numbering.levels.get(level));
+                    let format = numbering_level
+                        .and_then(|numbering_level| numbering_level.format.as_deref())
+                        .or_else(|| self.numberings.get(&id)?.format.as_deref());
+                    let tag = match format {
+                        Some("bullet") => "ul",
+                        _ => "ol",
+                    };
+                    close_lists(&mut html, &mut lists, level + 1);
+                    match lists.last() {
+                        Some(open) if lists.len() == level + 1 && *open == tag => {
+                            html += "</li>\n";
+                        }
+                        Some(_) if lists.len() == level + 1 => {
+                            close_lists(&mut html, &mut lists, level);
+                        }
+                        _ => (),
+                    }
+                    while lists.len() < level + 1 {
+                        let start = numbering_level
+                            .and_then(|numbering_level| numbering_level.start)
+                            .filter(|start| tag == "ol" && *start != 1);
+                        match start {
+                            Some(start) if lists.len() == level => {
+                                html += &format!("<{tag} start=\"{start}\">\n")
+                            }
+                            _ => html += &format!("<{tag}>\n"),
+                        }
+                        lists.push(tag);
+                        if lists.len() < level + 1 {
+                            html += "<li>";
+                        }
+                    }
+                    html += &format!("<li>{inline}");
+                }
+                _ => {
+                    close_lists(&mut html, &mut lists, 0);
+                    if !inline.is_empty() {
+                        html += &format!("<p>{inline}</p>\n");
+                    }
+                }
+            }
+        }
+        close_lists(&mut html, &mut lists, 0);
+
+        if !self.notes.is_empty() {
+            html += "<section class=\"footnotes\">\n";
+            for note in &self.notes {
+                let paragraphs: Vec<String> = note
+                    .paragraphs
+                    .iter()
+                    .map(|paragraph| {
+                        let style = paragraph.resolved_style(&self.styles);
+                        self.paragraph_html(paragraph, &style, options)
+                    })
+                    .collect();
+                html += &format!(
+                    "<p id=\"fn-{label}\"><sup>{label}</sup> {}</p>\n",
+                    paragraphs.join("<br/>"),
+                    label = escape(&note.label)
+                );
+            }
+            html += "</section>\n";
+        }
+
+        if options.standalone {
+            let title = self.title.as_deref().map(escape).unwrap_or_default();
+            let lang = match &self.language {
+                Some(language) => format!(" lang=\"{}\"", escape(language)),
+                None => String::new(),
+            };
+            html = format!(
+                "<!DOCTYPE html>\n<html{lang}>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n</head>\n<body>\n{html}</body>\n</html>\n"
+            );
+        }
+        html
+    }
+
+    /// The inline HTML of a paragraph, with the formatting of its blocks.
+    fn paragraph_html(
+        &self,
+        paragraph: &MarkdownParagraph,
+        style: &ParagraphStyle,
+        options: &HtmlOptions,
+    ) -> String {
+        let markdown_options = &options.markdown;
+        let mut html = String::new();
+        for block in &paragraph.blocks {
+            let inline = match block.text_type {
+                TextType::Image => {
+                    let block = match markdown_options.base64_images {
+                        true => self.inline_image(block),
+                        false => block.clone(),
+                    };
+                    let src = link_target(&block.text);
+                    let src = markdown_options
+                        .image_path(src)
+                        .unwrap_or_else(|| src.to_string());
+                    format!(
+                        r#"<img src="{}" alt="{}">"#,
+                        escape(&src),
+                        escape(link_text(&block.text))
+                    )
+                }
+                TextType::Link => format!(
+                    r#"<a href="{}">{}</a>"#,
+                    escape(link_target(&block.text)),
+                    escape(link_text(&block.text))
+                ),
+                TextType::Math => format!(
+                    r#"<span class="math inline">\({}\)</span>"#,
+                    escape(&block.text)
+                ),
+                TextType::MathBlock => format!(
+                    r#"<span class="math display">\[{}\]</span>"#,
+                    escape(&block.text)
+                ),
+                TextType::NoteReference => format!(
+                    r##"<sup><a href="#fn-{label}">{label}</a></sup>"##,
+                    label = escape(&block.text)
+                ),
+                // The bookmark is an anchor already.
+                TextType::BookmarkLink => block.text.clone(),
+                TextType::CommentReference if markdown_options.inline_comments => {
+                    format!("<!-- {} -->", block.text.replace("--", "- -"))
+                }
+                TextType::CommentReference => String::new(),
+                TextType::ClearBreak => r#"<br clear="all"/>"#.to_string(),
+                TextType::LineBreak => "<br/>".to_string(),
+                _ => {
+                    let block_style = block.resolved_style(style);
+                    let mut inline = escape(&block.text);
+                    if let Some(ruby) = &block.ruby {
+                        inline = format!("<ruby>{inline}<rt>{}</rt></ruby>", escape(ruby));
+                    }
+                    for (enabled, tag) in [
+                        (block_style.code, "code"),
+                        (block_style.bold, "strong"),
+                        (block_style.italics, "em"),
+                        (block_style.underline, "u"),
+                        (block_style.strike, "s"),
+                        (block_style.superscript, "sup"),
+                        (block_style.subscript, "sub"),
+                    ] {
+                        if enabled && !block.text.trim().is_empty() {
+                            inline = format!("<{tag}>{inline}</{tag}>");
+                        }
+                    }
+                    let mut css = vec![];
+                    if let Some(color) = &block_style.color {
+                        css.push(format!("color:#{color}"));
+                    }
+                    if let Some(rgb) = block_style.highlight.as_deref().and_then(highlight_rgb) {
+                        css.push(format!("background:#{rgb}"));
+                    }
+                    if !css.is_empty() {
+                        inline = format!(r#"<span style="{}">{inline}</span>"#, css.join(";"));
+                    }
+                    inline
+                }
+            };
+            html += &inline;
+        }
+        html.trim().to_string()
+    }
+
+    /// A table, with `colspan` and `rowspan` for merged cells and `<th>` in header rows.
+    fn table_html(&self, table: &MarkdownTable, options: &HtmlOptions) -> String {
+        let mut html = String::from("<table>\n");
+        for (r, row) in table.iter().enumerate() {
+            html += "<tr>";
+            let tag = if row.is_header { "th" } else { "td" };
+            let mut column = 0;
+            for cell in &row.cells {
+                let span = cell.span();
+                column += span;
+                if cell.v_merge == Some(VMergeKind::Continue) {
+                    continue;
+                }
+                let mut attributes = String::new();
+                if span > 1 {
+                    attributes += &format!(" colspan=\"{span}\"");
+                }
+                // The rows below that continue the merge, by the cell in the same grid column.
+                let rows = table[r + 1..]
+                    .iter()
+                    .take_while(|below| {
+                        below
+                            .grid_cells()
+                            .get(column - span)
+                            .is_some_and(|below| below.v_merge == Some(VMergeKind::Continue))
+                    })
+                    .count();
+                if cell.v_merge == Some(VMergeKind::Restart) && rows > 0 {
+                    attributes += &format!(" rowspan=\"{}\"", rows + 1);
+                }
+                let paragraphs: Vec<String> = cell
+                    .paragraphs
+                    .iter()
+                    .map(|paragraph| {
+                        let style = paragraph.resolved_style(&self.styles);
+                        self.paragraph_html(paragraph, &style, options)
+                    })
+                    .filter(|paragraph| !paragraph.is_empty())
+                    .collect();
+                html += &format!("<{tag}{attributes}>{}</{tag}>", paragraphs.join("<br/>"));
+            }
+            html += "</tr>\n";
+        }
+        html + "</table>\n"
+    }
+}
+
+/// Close the open lists above `level`, e.g. all of them for 0.
+fn close_lists(html: &mut String, lists: &mut Vec<&str>, level: usize) {
+    while lists.len() > level {
+        if let Some(tag) = lists.pop() {
+            *html += &format!("</li>\n</{tag}>\n");
+        }
+    }
+}
+
+/// The nodes of a SmartArt diagram as a nested list.
+fn smart_art_html(nodes: &[SmartArtNode]) -> String {
+    if nodes.is_empty() {
+        return String::new();
+    }
+    let items: String = nodes
+        .iter()
+        .map(|node| {
+            format!(
+                "<li>{}{}</li>\n",
+                escape(&node.text),
+                smart_art_html(&node.children)
+            )
+        })
+        .collect();
+    format!("<ul>\n{items}</ul>\n")
+}
+
+/// Escape the characters that HTML would read as markup, e.g. `&lt;` for `<`.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[test]
+fn test_escape() {
+    assert_eq!(escape(r#"a < b & "c""#), "a &lt; b &amp; &quot;c&quot;");
+}
@@ -0,0 +1,142 @@
+//! Size and cropping of images, read from the raw DrawingML or VML.
+
+use crate::package::{is_element, A_NS, R_NS};
+use roxmltree::Node;
+use serde::Serialize;
+
+pub const WP_NS: &str = "http://schemas.openxmlformats.org/drawingml/2006/wordprocessingDrawing";
+
+/// English Metric Units per pixel, at 96 dpi.
+const EMU_PER_PIXEL: f64 = 9525.0;
+
+/// Pixels (at 96 dpi) per point.
+const PIXELS_PER_POINT: f64 = 96.0 / 72.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageProperties {
+    /// Displayed width in pixels (at 96 dpi)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    /// Displayed height in pixels (at 96 dpi)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    /// Displayed width divided by the displayed height
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aspect_ratio: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crop: Option<ImageCrop>,
+}
+
+/// The part of the source image that is cut off at each side, in percent.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImageCrop {
+    pub left: f64,
+    pub top: f64,
+    pub right: f64,
+    pub bottom: f64,
+}
+
+impl ImageProperties {
+    /// Read the properties of the image with relationship id `embed` in a raw run.
+    pub fn from_run(run: Node, embed: &str) -> Option<Self> {
+        let blip = run.descendants().find(|node| {
+            is_element(node, A_NS, "blip") && node.attribute((R_NS, "embed")) == Some(embed)
+        })?;
+        let extent = blip
+            .ancestors()
+            .find(|node| is_element(node, WP_NS, "inline") || is_element(node, WP_NS, "anchor"))
+            .and_then(|drawing| {
+                drawing
+                    .children()
+                    .find(|node| is_element(node, WP_NS, "extent"))
+            });
+        let emu = |name: &str| -> Option<f64> { extent?.attribute(name)?.parse().ok() };
+        let (cx, cy) = (emu("cx"), emu("cy"));
+
+        // The source rectangle is stored in 1/1000th of a percent.
+        let crop = blip
+            .parent()
+            .and_then(|fill| {
+                fill.children()
+                    .find(|node| is_element(node, A_NS, "srcRect"))
+            })
+            .map(|rect| {
+                let percent = |name: &str| -> f64 {
+                    rect.attribute(name)
+                        .and_then(|value| value.parse::<f64>().ok())
+                        .unwrap_or(0.0)
+                        / 1000.0
+                };
+                ImageCrop {
+                    left: percent("l"),
+                    top: percent("t"),
+                    right: percent("r"),
+                    bottom: percent("b"),
+                }
+            })
+            .filter(|crop| crop != &ImageCrop::none());
+
+        Some(ImageProperties {
+            width: cx.map(|cx| (cx / EMU_PER_PIXEL).round() as u32),
+            height: cy.map(|cy| (cy / EMU_PER_PIXEL).round() as u32),
+            aspect_ratio: match (cx, cy) {
+                (Some(cx), Some(cy)) if cy > 0.0 => Some(cx / cy),
+                _ => None,
+            },
+            crop,
+        })
+    }
+
+    /// Read the size of a VML image from the `style` of its `v:shape`, e.g.
+    /// `width:120pt;height:90pt`.
+    pub fn from_vml_shape(shape: Node) -> Option<Self> {
+        let style = shape.attribute("style")?;
+        let length = |name: &str| -> Option<f64> {
+            let value = style.split(';').find_map(|declaration| {
+                let (property, value) = declaration.split_once(':')?;
+                (property.trim() == name).then(|| value.trim())
+            })?;
+            let (number, factor) = [("pt", PIXELS_PER_POINT), ("in", 96.0), ("px", 1.0)]
+                .into_iter()
+                .find_map(|(unit, factor)| Some((value.strip_suffix(unit)?, factor)))?;
+            Some(number.trim().parse::<f64>().ok()? * factor)
+        };
+        let (width, height) = (length("width"), length("height"));
+        if width.is_none() && height.is_none() {
+            return None;
+        }
+        Some(ImageProperties {
+            width: width.map(|width| width.round() as u32),
+            height: height.map(|height| height.round() as u32),
+            aspect_ratio: match (width, height) {
+                (Some(width), Some(height)) if height > 0.0 => Some(width / height),
+                _ => None,
+            },
+            crop: None,
+        })
+    }
+}
+
+impl ImageCrop {
+    fn none() -> Self {
+        ImageCrop {
+            left: 0.0,
+            top: 0.0,
+            right: 0.0,
+            bottom: 0.0,
+        }
+    }
+}
+
+/// An image that is referenced by the markdown, for writing it to disk yourself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageManifestEntry {
+    /// The path used in the markdown, e.g. `./media/image1.png`
+    pub reference: String,
+    /// The file name of the image, e.g. `image1.png`
+    pub file_name: String,
+    /// The MIME type, when known from the file extension, e.g. `image/png`
+    pub mime_type: Option<&'static str>,
+    pub data: Vec<u8>,
+}
@@ -5,29 +5,83 @@
 //! ```
 //! use docx_parser::MarkdownDocument;
 //!
-//! let markdown_doc = MarkdownDocument::from_file("./test/tables.docx");
+//! let markdown_doc = MarkdownDocument::from_file("./test/tables.docx").unwrap();
 //! let markdown = markdown_doc.to_markdown(true);
-//! let json = markdown_doc.to_json(true);
+//! let json = markdown_doc.to_json(true).unwrap();
 //! println!("\n\n{}", markdown);
 //! println!("\n\n{}", json);
 //! ```
 
+mod alt_chunk;
+mod chart;
+mod comments;
+mod error;
+mod fields;
+mod header_footer;
+mod html;
+mod image;
+mod lint;
+mod math;
+mod notes;
+mod options;
+mod package;
+mod pandoc;
+mod permissions;
+mod revisions;
+mod rst;
+mod smart_art;
+mod text;
 mod utils;
 
+use alt_chunk::AltChunk;
+pub use chart::MarkdownChart;
+use chart::C_NS;
+pub use comments::MarkdownComment;
 use docx_rust::core::Core;
 use docx_rust::document::BodyContent::{Paragraph, Run, Sdt, SectionProperty, Table, TableCell};
 use docx_rust::document::{ParagraphContent, RunContent, TableCellContent, TableRowContent};
-use docx_rust::formatting::{NumberFormat, OnOffOnlyType, ParagraphProperty};
+use docx_rust::formatting::{
+    CharacterProperty, NumberFormat, OnOffOnlyType, ParagraphProperty, UnderlineStyle,
+};
 use docx_rust::media::MediaType;
 use docx_rust::styles::StyleType;
 use docx_rust::DocxFile;
+pub use error::DocxParseError;
+use hard_xml::XmlRead;
+pub use image::{ImageCrop, ImageManifestEntry, ImageProperties};
+pub use lint::{LintViolation, MarkdownLintError};
+use math::{equation_number, math_latex, split_tag};
+pub use notes::{MarkdownNote, NoteKind};
+pub use options::{
+    BlockPostprocess, CellParagraphJoin, EmphasisMarker, FileLinkHandling, FontSizeJson,
+    HtmlOptions, ImageJsonMode, ImageSyntax, ListIndent, MarkdownOptions, MarkdownOptionsBuilder,
+    MathOutput, ParagraphSpacing, PlainTextOptions, RubySyntax, StyleBlock, StyleOverride,
+    TrackChanges,
+};
+use package::{
+    is_element, outer_xml, part_path, text_content, w_children, Package, M_NS, O_NS, R_NS, V_NS,
+    W_NS,
+};
+pub use permissions::PermissionRange;
+pub use revisions::{RevisionEvent, RevisionKind};
+use roxmltree::Node;
 use serde::Serialize;
-use std::collections::HashMap;
+use smart_art::DGM_NS;
+pub use smart_art::{MarkdownSmartArt, SmartArtNode};
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs::File;
-use std::io::{Read, Seek};
+use std::io::{self, Cursor, Read, Seek, Write};
+use std::ops::{Bound, RangeBounds};
 use std::path::Path;
 use std::str::FromStr;
-use utils::{max_lengths_per_column, save_image_to_file, serialize_images, table_row_to_markdown};
+use utils::{
+    bidi_isolate, bullet_glyph, csv_field, data_url, escape_markdown, heading_style_level,
+    highlight_rgb, image_mime_type, is_file_link, is_inline_code, link_target, link_text,
+    max_lengths_per_column, number_to_words, ordinal_suffix, save_image_to_file, serialize_images,
+    slugify, table_row_to_markdown, to_letters, to_roman, wrap_markdown,
+};
 
 #[derive(Debug, PartialEq, Eq, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -43,6 +97,49 @@ pub struct BlockStyle {
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Size is specified in points x 2, so size 19 is equal to 9.5pt
     pub size: Option<isize>,
+    /// The character style of the run, e.g. `Strong`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style_id: Option<String>,
+    /// The font family, e.g. `Arial`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font: Option<String>,
+    /// The font color as hex RGB, e.g. `FF0000`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// The highlight color, e.g. `yellow`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub highlight: Option<String>,
+    /// Inline code, i.e. shaded text in a monospace font
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub code: bool,
+    /// Right-to-left text (`w:rtl`), e.g. an Arabic phrase
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub rtl: bool,
+    /// Raised text (`w:vertAlign`), e.g. the `2` of `x²`
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub superscript: bool,
+    /// Lowered text (`w:vertAlign`), e.g. the `2` of `H₂O`
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub subscript: bool,
+    /// The formatting that is switched off explicitly, e.g. with `<w:b w:val="0"/>`, which
+    /// overrides the formatting inherited from the paragraph
+    #[serde(skip)]
+    pub switched_off: SwitchedOff,
+}
+
+/// Formatting toggles that run properties switch off explicitly.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SwitchedOff {
+    pub bold: bool,
+    pub italics: bool,
+    pub underline: bool,
+    pub strike: bool,
+}
+
+impl Default for BlockStyle {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl BlockStyle {
@@ -53,17 +150,125 @@ impl BlockStyle {
             underline: false,
             strike: false,
             size: None,
+            style_id: None,
+            font: None,
+            color: None,
+            highlight: None,
+            code: false,
+            rtl: false,
+            superscript: false,
+            subscript: false,
+            switched_off: SwitchedOff::default(),
+        }
+    }
+
+    /// The formatting that all blocks have in common, e.g. bold when every block is bold.
+    /// Returns `None` when there is none.
+    pub fn common(blocks: &[TextBlock]) -> Option<BlockStyle> {
+        let mut styles = blocks
+            .iter()
+            .map(|block| block.style.clone().unwrap_or_else(BlockStyle::new));
+        let mut common = styles.next()?;
+        for style in styles {
+            common.bold &= style.bold;
+            common.italics &= style.italics;
+            common.underline &= style.underline;
+            common.strike &= style.strike;
+            common.code &= style.code;
+            common.rtl &= style.rtl;
+            common.superscript &= style.superscript;
+            common.subscript &= style.subscript;
+            common.switched_off.bold &= style.switched_off.bold;
+            common.switched_off.italics &= style.switched_off.italics;
+            common.switched_off.underline &= style.switched_off.underline;
+            common.switched_off.strike &= style.switched_off.strike;
+            if common.size != style.size {
+                common.size = None;
+            }
+            if common.style_id != style.style_id {
+                common.style_id = None;
+            }
+            if common.font != style.font {
+                common.font = None;
+            }
+            if common.color != style.color {
+                common.color = None;
+            }
+            if common.highlight != style.highlight {
+                common.highlight = None;
+            }
         }
+        (common != BlockStyle::new()).then_some(common)
+    }
+
+    /// The font size in points, e.g. 9.5 for a size of 19 half-points. As sizes are stored in
+    /// half-points, this is always a multiple of 0.5.
+    pub fn size_in_points(&self) -> Option<f64> {
+        self.size.map(|size| size as f64 / 2.0)
+    }
+
+    /// A named (t-shirt) size for the font size, e.g. `m` for 11pt: `xs` below 9pt, `s` below
+    /// 11pt, `m` below 14pt, `l` below 18pt, `xl` below 26pt and `xxl` from there.
+    pub fn size_name(&self) -> Option<&'static str> {
+        let points = self.size_in_points()?;
+        let name = match points {
+            _ if points < 9.0 => "xs",
+            _ if points < 11.0 => "s",
+            _ if points < 14.0 => "m",
+            _ if points < 18.0 => "l",
+            _ if points < 26.0 => "xl",
+            _ => "xxl",
+        };
+        Some(name)
     }
 
+    /// Layer `other` on top of this style. Formatting that is switched on in either is kept,
+    /// unless `other` switches it off explicitly.
     pub fn combine_with(&mut self, other: &BlockStyle) {
-        self.bold = other.bold;
-        self.italics = other.italics;
-        self.underline = other.underline;
-        self.strike = other.strike;
+        let layer = |on: &mut bool, off: &mut bool, other_on: bool, other_off: bool| {
+            *on = other_on || (*on && !other_off);
+            *off = other_off || (*off && !other_on);
+        };
+        let off = &mut self.switched_off;
+        let other_off = &other.switched_off;
+        layer(&mut self.bold, &mut off.bold, other.bold, other_off.bold);
+        layer(
+            &mut self.italics,
+            &mut off.italics,
+            other.italics,
+            other_off.italics,
+        );
+        layer(
+            &mut self.underline,
+            &mut off.underline,
+            other.underline,
+            other_off.underline,
+        );
+        layer(
+            &mut self.strike,
+            &mut off.strike,
+            other.strike,
+            other_off.strike,
+        );
+        self.code |= other.code;
+        self.rtl |= other.rtl;
+        self.superscript |= other.superscript;
+        self.subscript |= other.subscript;
         if let Some(size) = other.size {
             self.size = Some(size);
         }
+        if let Some(style_id) = &other.style_id {
+            self.style_id = Some(style_id.clone());
+        }
+        if let Some(font) = &other.font {
+            self.font = Some(font.clone());
+        }
+        if let Some(color) = &other.color {
+            self.color = Some(color.clone());
+        }
+        if let Some(highlight) = &other.highlight {
+            self.highlight = Some(highlight.clone());
+        }
     }
 }
 
@@ -78,6 +283,76 @@ pub struct MarkdownNumbering {
     pub format: Option<String>, // NumberFormat
     #[serde(skip_serializing_if = "Option::is_none")]
     pub level_text: Option<String>,
+    /// The format of each level, starting at level 0
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub levels: Vec<MarkdownNumberingLevel>,
+}
+
+impl MarkdownNumbering {
+    /// Fill in the `lvlText` template of a level, e.g. `(%1)` or `%1.%2.`, where `%N` is the
+    /// current number of level N (counting from 1), formatted in the number format of that level.
+    /// Levels that have not been used yet count as 1.
+    fn apply_level_text(
+        &self,
+        level_text: &str,
+        current_number: impl Fn(isize) -> Option<usize>,
+        language: Option<&str>,
+    ) -> String {
+        let mut text = String::new();
+        let mut chars = level_text.chars().peekable();
+        while let Some(c) = chars.next() {
+            match (c, chars.peek().and_then(|next| next.to_digit(10))) {
+                ('%', Some(digit)) if digit > 0 => {
+                    chars.next();
+                    let level = digit as isize - 1;
+                    let format = self
+                        .levels
+                        .get(level as usize)
+                        .and_then(|level| level.format.as_ref())
+                        .and_then(|format| NumberFormat::from_str(format).ok())
+                        .unwrap_or(NumberFormat::Decimal);
+                    let number = current_number(level).unwrap_or(1);
+                    text += &number_text(number, &format, language);
+                }
+                _ => text.push(c),
+            }
+        }
+        text
+    }
+}
+
+/// A number in the given format, without any punctuation, e.g. `iv` or `D`.
+fn number_text(number: usize, format: &NumberFormat, language: Option<&str>) -> String {
+    match format {
+        NumberFormat::UpperRoman => to_roman(number).to_uppercase(),
+        NumberFormat::LowerRoman => to_roman(number),
+        NumberFormat::UpperLetter => to_letters(number).to_uppercase(),
+        NumberFormat::LowerLetter => to_letters(number),
+        NumberFormat::Ordinal => match ordinal_suffix(number, language) {
+            Some(suffix) => format!("{number}{suffix}"),
+            None => number.to_string(),
+        },
+        NumberFormat::CardinalText | NumberFormat::OrdinalText => {
+            let ordinal = matches!(format, NumberFormat::OrdinalText);
+            number_to_words(number, ordinal, language).unwrap_or_else(|| number.to_string())
+        }
+        _ => number.to_string(),
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkdownNumberingLevel {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>, // NumberFormat
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level_text: Option<String>,
+    /// The font of the list marker, e.g. `Wingdings` for a symbol bullet
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font: Option<String>,
+    /// The number of the first item (`w:start`, or the `w:startOverride` of the numbering)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<usize>,
 }
 
 #[derive(Debug, Default, Clone, Serialize)]
@@ -91,6 +366,12 @@ pub struct ParagraphStyle {
     pub numbering: Option<MarkdownNumbering>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub page_break_before: Option<bool>,
+    /// Whether the space between paragraphs of the same style is left out, e.g. for a tight list.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contextual_spacing: Option<bool>,
+    /// Whether the paragraph is right-to-left (`w:bidi`), e.g. Arabic or Hebrew
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bidi: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub style: Option<BlockStyle>,
 }
@@ -102,6 +383,8 @@ impl ParagraphStyle {
             outline_lvl: None,
             numbering: None,
             page_break_before: None,
+            contextual_spacing: None,
+            bidi: None,
             style: None,
         }
     }
@@ -110,6 +393,8 @@ impl ParagraphStyle {
         self.style_id = self.style_id.clone().or_else(|| other.style_id.clone());
         self.outline_lvl = self.outline_lvl.or(other.outline_lvl);
         self.page_break_before = self.page_break_before.or(other.page_break_before);
+        self.contextual_spacing = self.contextual_spacing.or(other.contextual_spacing);
+        self.bidi = self.bidi.or(other.bidi);
         if self.numbering.is_none() {
             self.numbering = other.numbering.clone()
         }
@@ -142,6 +427,7 @@ impl<'a> From<&'a ParagraphProperty<'a>> for ParagraphStyle {
                 indent_level: numbering.level.as_ref().map(|level| level.value),
                 format: None,
                 level_text: None,
+                levels: vec![],
             });
         }
         if !paragraph_property.r_pr.is_empty() {
@@ -153,19 +439,7 @@ impl<'a> From<&'a ParagraphProperty<'a>> for ParagraphStyle {
                     if let Some(size) = &character_property.size {
                         block_style.size = Some(size.value);
                     }
-                    if character_property.bold.is_some() {
-                        block_style.bold = true;
-                    }
-                    if character_property.underline.is_some() {
-                        block_style.underline = true;
-                    }
-                    if character_property.italics.is_some() || character_property.emphasis.is_some()
-                    {
-                        block_style.italics = true;
-                    }
-                    if character_property.strike.is_some() || character_property.dstrike.is_some() {
-                        block_style.strike = true;
-                    }
+                    apply_toggles(&mut block_style, character_property);
                 });
             paragraph_style.style = Some(block_style);
         }
@@ -173,7 +447,7 @@ impl<'a> From<&'a ParagraphProperty<'a>> for ParagraphStyle {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum TextType {
     Text,
     Image,
@@ -188,15 +462,78 @@ pub enum TextType {
     CodeBlock,
     HeaderBlock,
     BookmarkLink,
+    Math,
+    MathBlock,
+    /// A reference to a footnote or endnote, with the label of the note as text
+    NoteReference,
+    /// A break that clears floating content (`w:br w:clear="all"`), e.g. after a floating image
+    ClearBreak,
+    /// Text with a phonetic guide (`w:ruby`), see [`TextBlock::ruby`]
+    Ruby,
+    /// A manual line break (`w:br`), e.g. from Shift+Enter
+    LineBreak,
+    /// A reference to a comment, with the comment as text, see [`MarkdownOptions::inline_comments`]
+    CommentReference,
+}
+
+/// What a link points to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum LinkKind {
+    /// A bookmark in the document
+    Internal,
+    /// A web page or other URL
+    External,
+    /// A `mailto:` link
+    Email,
+    /// A local or network file
+    File,
 }
 
-#[derive(Debug, PartialEq, Eq, Serialize)]
+impl LinkKind {
+    /// The kind of a link target, where `external` is whether its relationship has
+    /// `TargetMode="External"`, which makes a relative path a file link.
+    pub fn from_target(target: &str, external: bool) -> Self {
+        if target.starts_with('#') {
+            LinkKind::Internal
+        } else if target.to_ascii_lowercase().starts_with("mailto:") {
+            LinkKind::Email
+        } else if is_file_link(target, external) {
+            LinkKind::File
+        } else {
+            LinkKind::External
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TextBlock {
     pub text_type: TextType,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub style: Option<BlockStyle>,
     pub text: String,
+    /// Revision save id of the run, or of the first run when runs with the same formatting are
+    /// merged, see [`MarkdownOptions::track_rsids`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rsid_r: Option<String>,
+    /// Revision save id of the run properties, see [`MarkdownOptions::track_rsids`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rsid_r_pr: Option<String>,
+    /// Size and cropping of an image block
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<ImageProperties>,
+    /// The relationship id (e.g. `rId5`) of the image or link target in the original package
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rel_id: Option<String>,
+    /// What a link points to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link_kind: Option<LinkKind>,
+    /// The target mode of the link relationship, e.g. `External`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_mode: Option<String>,
+    /// The phonetic guide (ruby text) of a ruby block, e.g. furigana, whose text is the base text
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ruby: Option<String>,
 }
 
 impl TextBlock {
@@ -205,50 +542,270 @@ impl TextBlock {
             style,
             text,
             text_type,
+            rsid_r: None,
+            rsid_r_pr: None,
+            image: None,
+            rel_id: None,
+            link_kind: None,
+            target_mode: None,
+            ruby: None,
         }
     }
 
-    pub fn to_markdown(&self, paragraph_style: &ParagraphStyle) -> String {
-        let mut markdown = self.text.clone();
+    pub fn to_markdown(
+        &self,
+        paragraph_style: &ParagraphStyle,
+        options: &MarkdownOptions,
+    ) -> String {
+        match (&self.text_type, &options.math_output) {
+            (TextType::Math | TextType::MathBlock, MathOutput::Placeholder(placeholder)) => {
+                return match split_tag(&self.text) {
+                    (_, Some(number)) => format!("{placeholder} ({number})"),
+                    (_, None) => placeholder.clone(),
+                };
+            }
+            (TextType::Math, MathOutput::Latex) => return format!("${}$", self.text),
+            (TextType::MathBlock, MathOutput::Latex) => return format!("$${}$$", self.text),
+            (TextType::NoteReference, _) => return format!("[^{}]", self.text),
+            (TextType::CommentReference, _) => {
+                if !options.inline_comments {
+                    return String::new();
+                }
+                // A double hyphen would end the HTML comment, so no two hyphens are adjacent.
+                let mut text = self.text.clone();
+                while text.contains("--") {
+                    text = text.replace("--", "- -");
+                }
+                return format!("<!-- {text} -->");
+            }
+            // A hard line break, unlike a single line break in markdown.
+            (TextType::LineBreak, _) => return "  \n".to_string(),
+            (TextType::ClearBreak, _) => {
+                return match options.image_syntax {
+                    ImageSyntax::HtmlImg => r#"<br clear="all"/>"#.to_string(),
+                    ImageSyntax::Markdown => "\n\n".to_string(),
+                };
+            }
+            _ => (),
+        }
+        if self.text_type == TextType::Image && options.image_syntax == ImageSyntax::HtmlImg {
+            return self.to_html_img(options);
+        }
 
-        let mut style = if self.style.is_some() {
-            self.style.as_ref().unwrap().clone()
-        } else {
-            BlockStyle::new()
+        let style = self.resolved_style(paragraph_style);
+        let mut markdown = match self.text_type == TextType::Text && !style.code {
+            // Characters like `*` are text, not emphasis.
+            true => escape_markdown(&self.text.replace('\t', &options.tab_replacement)),
+            false => match options.image_path(link_target(&self.text)) {
+                Some(path) if self.text_type == TextType::Image => {
+                    format!("![{}]({path})", link_text(&self.text))
+                }
+                _ => self.text.clone(),
+            },
         };
 
-        if let Some(block_style) = &paragraph_style.style {
-            style.combine_with(block_style);
-        };
+        if let Some(ruby) = &self.ruby {
+            markdown = match options.ruby_syntax {
+                RubySyntax::Parentheses => format!("{markdown}({ruby})"),
+                RubySyntax::Html => format!("<ruby>{markdown}<rt>{ruby}</rt></ruby>"),
+            };
+        }
+
+        if style.code && self.text_type == TextType::Text {
+            markdown = match markdown.contains('`') {
+                true => format!("`` {markdown} ``"),
+                false => format!("`{markdown}`"),
+            };
+        }
 
         // Add bold formatting if enabled
         if style.bold {
-            markdown = format!("**{markdown}**");
+            let marker = options.bold_marker.as_str().repeat(2);
+            markdown = format!("{marker}{markdown}{marker}");
         }
 
         // Add italic formatting if enabled
         if style.italics {
-            markdown = format!("*{markdown}*");
+            let marker = options.italic_marker.as_str();
+            markdown = format!("{marker}{markdown}{marker}");
         }
 
-        // Add underline formatting if enabled
+        // Add underline formatting if enabled, as HTML when `__` already means bold
         if style.underline {
-            markdown = format!("__{markdown}__");
+            markdown = match options.bold_marker {
+                EmphasisMarker::Asterisk => format!("__{markdown}__"),
+                EmphasisMarker::Underscore => format!("<u>{markdown}</u>"),
+            };
         }
 
         // Add strike-through formatting if enabled
         if style.strike {
             markdown = format!("~~{markdown}~~");
         }
+
+        // Note references are raised anyway.
+        if self.text_type == TextType::Text {
+            if style.superscript {
+                markdown = format!("<sup>{markdown}</sup>");
+            } else if style.subscript {
+                markdown = format!("<sub>{markdown}</sub>");
+            }
+
+            let mut css = vec![];
+            if let Some(color) = &style.color {
+                css.push(format!("color:#{color}"));
+            }
+            if let Some(rgb) = style.highlight.as_deref().and_then(highlight_rgb) {
+                css.push(format!("background:#{rgb}"));
+            }
+            if !css.is_empty() {
+                markdown = format!(r#"<span style="{}">{markdown}</span>"#, css.join(";"));
+            }
+        }
+
+        if let Some((open, close)) = style
+            .style_id
+            .as_ref()
+            .and_then(|style_id| options.run_style_map.get(style_id))
+        {
+            markdown = format!("{open}{markdown}{close}");
+        }
+
+        // Runs against the direction of the paragraph, e.g. an Arabic phrase in an English
+        // sentence, are isolated so that they don't reorder the text around them.
+        if self.text_type == TextType::Text && style.rtl != paragraph_style.bidi.unwrap_or(false) {
+            markdown = bidi_isolate(&markdown, style.rtl);
+        }
         markdown
     }
+
+    /// An image block as an HTML `<img>` element, with its displayed size when known.
+    fn to_html_img(&self, options: &MarkdownOptions) -> String {
+        let alt = link_text(&self.text).replace('"', "&quot;");
+        let src = link_target(&self.text);
+        let src = options.image_path(src).unwrap_or_else(|| src.to_string());
+        let mut img = format!(r#"<img src="{src}" alt="{alt}""#);
+        if let Some(image) = &self.image {
+            if let Some(width) = image.width {
+                img += &format!(r#" width="{width}""#);
+            }
+            if let Some(height) = image.height {
+                img += &format!(r#" height="{height}""#);
+            }
+        }
+        img + ">"
+    }
+
+    /// The style of the block, on top of the run properties of its paragraph, which every run
+    /// inherits.
+    pub fn resolved_style(&self, paragraph_style: &ParagraphStyle) -> BlockStyle {
+        let mut style = paragraph_style.style.clone().unwrap_or_default();
+
+        if let Some(block_style) = &self.style {
+            style.combine_with(block_style);
+        };
+        style
+    }
+}
+
+/// The state that is shared while converting the content of a docx file.
+struct ParseContext<'a> {
+    options: &'a MarkdownOptions,
+    /// Targets of the document relationships, keyed by relationship id, so that links and images
+    /// are looked up without going through all relationships
+    targets: HashMap<String, String>,
+    /// Target modes of the document relationships, which docx-rust doesn't keep
+    target_modes: HashMap<String, String>,
+    /// The root element of `[Content_Types].xml`, for the content types of embedded parts
+    content_types: Option<Node<'a, 'a>>,
+    /// The referenced notes, in order of their first reference
+    note_references: RefCell<Vec<(NoteKind, String)>>,
+    /// Content that could not be converted, see [`MarkdownOptions::strict`]
+    dropped: RefCell<Vec<String>>,
+    /// The formatting of the character styles and linked paragraph styles, keyed by style id
+    character_styles: HashMap<String, BlockStyle>,
+    /// The comments, keyed by comment id
+    comments: HashMap<String, MarkdownComment>,
+}
+
+impl ParseContext<'_> {
+    /// The target of a document relationship, e.g. `media/image1.png`.
+    fn target(&self, id: &str) -> Option<&str> {
+        self.targets.get(id).map(String::as_str)
+    }
+
+    /// The label of a note, numbering the notes of each kind in the order they are first
+    /// referenced.
+    fn note_label(&self, kind: NoteKind, id: &str) -> String {
+        let mut references = self.note_references.borrow_mut();
+        let position = match references
+            .iter()
+            .position(|(other_kind, other_id)| *other_kind == kind && other_id == id)
+        {
+            Some(position) => position,
+            None => {
+                references.push((kind, id.to_string()));
+                references.len() - 1
+            }
+        };
+        NoteKind::labels(&references[..=position])
+            .pop()
+            .unwrap_or_default()
+    }
+
+    /// Record content that is lost in the conversion.
+    fn drop_content(&self, warning: String) {
+        self.dropped.borrow_mut().push(warning);
+    }
+}
+
+/// Stands in for a pipe in the text of a table cell until the cell is rendered, when it is
+/// escaped, from the Unicode private use area.
+const CELL_PIPE: &str = "\u{E000}";
+
+/// Inline elements that docx-rust doesn't model, and that are read from the raw paragraph XML.
+const RAW_INLINES: [(&str, &str); 7] = [
+    (M_NS, "oMath"),
+    (M_NS, "oMathPara"),
+    (W_NS, "smartTag"),
+    (W_NS, "customXml"),
+    (W_NS, "ins"),
+    (W_NS, "del"),
+    (W_NS, "fldSimple"),
+];
+
+/// A smart tag or custom XML element that wraps part of a paragraph.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmartTag {
+    /// The type of the tag, e.g. `place` or `PersonName`
+    pub element: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+    /// The tagged text
+    pub text: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct MarkdownParagraph {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub style: Option<ParagraphStyle>,
     pub blocks: Vec<TextBlock>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub smart_tags: Vec<SmartTag>,
+    /// Revision save id of the paragraph, see [`MarkdownOptions::track_rsids`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rsid_r: Option<String>,
+    /// Revision save id of the paragraph mark, see [`MarkdownOptions::track_rsids`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rsid_r_pr: Option<String>,
+}
+
+impl Default for MarkdownParagraph {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl MarkdownParagraph {
@@ -256,6 +813,9 @@ impl MarkdownParagraph {
         MarkdownParagraph {
             style: None,
             blocks: vec![],
+            smart_tags: vec![],
+            rsid_r: None,
+            rsid_r_pr: None,
         }
     }
 
@@ -263,26 +823,29 @@ impl MarkdownParagraph {
     pub fn to_markdown(
         &self,
         styles: &HashMap<String, ParagraphStyle>,
-        numberings: &mut HashMap<isize, usize>,
+        numberings: &mut HashMap<(isize, isize), usize>,
         doc: &MarkdownDocument,
+        options: &MarkdownOptions,
     ) -> String {
         let mut markdown = String::new();
 
-        let mut style = if self.style.is_some() {
-            self.style.as_ref().unwrap().clone()
-        } else {
-            ParagraphStyle::default()
-        };
-
-        if let Some(style_id) = &style.style_id {
-            if let Some(doc_style) = styles.get(style_id) {
-                style.combine_with(doc_style);
-            }
-            // markdown += &format!("[{}]", style_id);
-        };
+        let style = self.rendered_style(styles, options);
+        let style_override = style
+            .style_id
+            .as_ref()
+            .and_then(|style_id| options.style_overrides.get(style_id));
+        if self.is_code_block(styles, options) {
+            let text = self.to_plain_text(&PlainTextOptions::default());
+            let fence = match text.contains("```") {
+                true => "````",
+                false => "```",
+            };
+            return format!("{fence}\n{text}\n{fence}");
+        }
 
         // Add outline level if available
-        if let Some(outline_lvl) = style.outline_lvl {
+        let heading_level = doc.paragraph_heading_level(self, options);
+        if let Some(outline_lvl) = heading_level {
             // Convert outline level to appropriate Markdown heading level
             let heading_level = match outline_lvl {
                 0 => "# ",
@@ -303,145 +866,466 @@ impl MarkdownParagraph {
                 }
             }
             if let Some(id) = numbering.id {
-                let format = match &doc.numberings[&id].format {
+                // Numbered headings without a list level, e.g. from a heading style, are at their
+                // outline level, so that a new chapter restarts the numbering of its sections.
+                let level = numbering.indent_level.or(style.outline_lvl).unwrap_or(0);
+                let doc_numbering = &doc.numberings[&id];
+                // Each level has its own format, e.g. `1.` / `a.` / `i.` in legal outlines.
+                let level_format = doc_numbering.levels.get(level.max(0) as usize);
+                let format = match level_format
+                    .and_then(|level_format| level_format.format.as_ref())
+                    .or(doc_numbering.format.as_ref())
+                {
                     Some(entry) => NumberFormat::from_str(entry).unwrap_or(NumberFormat::Decimal),
                     None => NumberFormat::Decimal,
                 };
-                let count = numberings.entry(id).or_insert(0); // Start numbering from 1
+                let level_text = level_format
+                    .and_then(|level_format| level_format.level_text.as_ref())
+                    .or(doc_numbering.level_text.as_ref());
+                // A new item restarts the numbering of the levels below it.
+                numberings
+                    .retain(|(other_id, other_level), _| *other_id != id || *other_level <= level);
+                // A list starts at the start value of its level, 1 by default.
+                let start = level_format
+                    .and_then(|level_format| level_format.start)
+                    .unwrap_or(1);
+                let count = numberings
+                    .entry((id, level))
+                    .or_insert(start.saturating_sub(1));
+                *count += 1;
+                let number = *count;
                 let numbering_symbol = match format {
-                    NumberFormat::UpperRoman => format!("{}.", ((*count) as u8 + b'I') as char),
-                    NumberFormat::LowerRoman => format!("{}.", ((*count) as u8 + b'i') as char),
-                    NumberFormat::UpperLetter => format!("{}.", ((*count) as u8 + b'A') as char),
-                    NumberFormat::LowerLetter => format!("{}.", ((*count) as u8 + b'a') as char),
-                    NumberFormat::Bullet => match &doc.numberings[&id].level_text {
+                    NumberFormat::Bullet => match level_text {
                         Some(level_text) if level_text.trim().is_empty() => " ".to_string(),
-                        _ => "-".to_string(),
+                        Some(level_text) if options.preserve_bullet_glyphs => bullet_glyph(
+                            level_text,
+                            level_format.and_then(|level_format| level_format.font.as_deref()),
+                        ),
+                        _ => match options.bullet_markers.len() {
+                            0 => "-".to_string(),
+                            len => options.bullet_markers[level.max(0) as usize % len].clone(),
+                        },
+                    },
+                    _ => match level_text {
+                        Some(level_text) if options.use_level_text => doc_numbering
+                            .apply_level_text(
+                                level_text,
+                                |placeholder_level| {
+                                    if placeholder_level == level {
+                                        Some(number)
+                                    } else {
+                                        numberings.get(&(id, placeholder_level)).copied()
+                                    }
+                                },
+                                doc.language(),
+                            ),
+                        _ => format!("{}.", number_text(number, &format, doc.language())),
                     },
-                    _ => format!("{}.", *count + 1),
                 };
-                *count += 1;
                 markdown += &format!("{numbering_symbol} ");
             }
         }
 
+        if let Some(marker) =
+            style_override.and_then(|style_override| style_override.marker.as_ref())
+        {
+            markdown += marker;
+        }
+
+        // A heading is a single line, where a tab only separates words.
+        let heading_options;
+        let options = match heading_level {
+            Some(_) if options.tab_replacement != " " => {
+                heading_options = MarkdownOptions {
+                    tab_replacement: " ".to_string(),
+                    ..options.clone()
+                };
+                &heading_options
+            }
+            _ => options,
+        };
+        let content_start = markdown.len();
         for block in &self.blocks {
-            markdown += &block.to_markdown(&style);
+            match block.text_type {
+                // A heading is a single line.
+                TextType::LineBreak if heading_level.is_some() => markdown += " ",
+                TextType::Image if options.base64_images => {
+                    markdown += &doc.inline_image(block).to_markdown(&style, options)
+                }
+                _ => markdown += &block.to_markdown(&style, options),
+            }
+        }
+        // Leading whitespace would turn the text into an indented code block, also after a list
+        // marker.
+        let content = markdown.split_off(content_start);
+        let content = content.trim_start_matches([' ', '\t']);
+        // Text that starts with `#` isn't a heading.
+        if content.starts_with('#') {
+            markdown.push('\\');
+        }
+        markdown += content;
+        if let Some(StyleBlock::Quote) =
+            style_override.and_then(|style_override| style_override.block)
+        {
+            let lines: Vec<String> = markdown.lines().map(|line| format!("> {line}")).collect();
+            markdown = lines.join("\n");
+        }
+        if let (Some(outline_lvl), true) = (heading_level, options.closed_atx_headings) {
+            markdown += " ";
+            markdown += match outline_lvl {
+                0 => "#",
+                1 => "##",
+                2 => "###",
+                3 => "####",
+                4 => "#####",
+                _ => "######",
+            };
         }
         markdown
     }
 
-    /// Convert a docx::Paragraph to a MarkdownParagraph
+    /// Check whether the paragraph has one of the [`MarkdownOptions::exclude_styles`].
+    fn is_excluded(&self, options: &MarkdownOptions) -> bool {
+        self.style
+            .as_ref()
+            .and_then(|style| style.style_id.as_ref())
+            .is_some_and(|style_id| options.exclude_styles.contains(style_id))
+    }
+
+    /// The text of the paragraph without any markup.
+    pub fn to_plain_text(&self, options: &PlainTextOptions) -> String {
+        let mut text = String::new();
+        for block in &self.blocks {
+            match block.text_type {
+                TextType::Image => {
+                    if options.include_image_alt_text {
+                        text += link_text(&block.text);
+                    }
+                }
+                TextType::Link => text += link_text(&block.text),
+                TextType::BookmarkLink | TextType::NoteReference | TextType::CommentReference => (),
+                TextType::ClearBreak | TextType::LineBreak => text += "\n",
+                _ => text += &block.text,
+            }
+        }
+        text
+    }
+
+    /// The resolved style that the paragraph is rendered with, where an outline level of the
+    /// paragraph itself only makes it a heading with [`MarkdownOptions::outline_level_as_heading`].
+    fn rendered_style(
+        &self,
+        styles: &HashMap<String, ParagraphStyle>,
+        options: &MarkdownOptions,
+    ) -> ParagraphStyle {
+        let mut style = self.resolved_style(styles);
+        if !options.outline_level_as_heading && self.has_bare_outline_level(styles) {
+            style.outline_lvl = None;
+        }
+        style
+    }
+
+    /// Whether a [`StyleOverride`] renders the paragraph as a code block.
+    fn is_code_block(
+        &self,
+        styles: &HashMap<String, ParagraphStyle>,
+        options: &MarkdownOptions,
+    ) -> bool {
+        self.resolved_style(styles)
+            .style_id
+            .and_then(|style_id| options.style_overrides.get(&style_id))
+            .and_then(|style_override| style_override.block)
+            == Some(StyleBlock::Code)
+    }
+
+    /// The heading level (0 for `#`) that the paragraph is rendered at: the level that a
+    /// [`StyleOverride`] gives its style, or else that of [`MarkdownParagraph::heading_level`].
+    /// A paragraph that is rendered as a code block has none.
+    fn rendered_heading_level(
+        &self,
+        styles: &HashMap<String, ParagraphStyle>,
+        options: &MarkdownOptions,
+    ) -> Option<isize> {
+        if self.is_code_block(styles, options) {
+            return None;
+        }
+        let style = self.rendered_style(styles, options);
+        match style
+            .style_id
+            .as_ref()
+            .and_then(|style_id| options.style_overrides.get(style_id))
+            .and_then(|style_override| style_override.heading_level)
+        {
+            Some(0) => None,
+            Some(level) => Some(level as isize - 1),
+            None => self.heading_level(&style, styles, options),
+        }
+    }
+
+    /// The heading level (0 for `#`) of the paragraph: its outline level, the level of its heading
+    /// style (e.g. `Heading2`) when the style is in `styles` but has no outline level, or, with
+    /// [`MarkdownOptions::detect_headings`], a level guessed from short bold text in a large font.
+    fn heading_level(
+        &self,
+        style: &ParagraphStyle,
+        styles: &HashMap<String, ParagraphStyle>,
+        options: &MarkdownOptions,
+    ) -> Option<isize> {
+        if style.outline_lvl.is_some() {
+            return style.outline_lvl;
+        }
+        if let Some(level) = style
+            .style_id
+            .as_deref()
+            .filter(|style_id| styles.contains_key(*style_id))
+            .and_then(heading_style_level)
+        {
+            return Some(level);
+        }
+        if !options.detect_headings || style.numbering.is_some() {
+            return None;
+        }
+        let text = self.to_plain_text(&PlainTextOptions::default());
+        let length = text.trim().chars().count();
+        if length <= options.heading_min_chars || length > 80 {
+            return None;
+        }
+        let text_styles: Vec<BlockStyle> = self
+            .blocks
+            .iter()
+            .filter(|block| block.text_type == TextType::Text && !block.text.trim().is_empty())
+            .map(|block| block.resolved_style(style))
+            .collect();
+        if text_styles.is_empty() || !text_styles.iter().all(|block_style| block_style.bold) {
+            return None;
+        }
+        // Sizes are in half points: 16pt and up is a top-level heading.
+        match text_styles
+            .iter()
+            .filter_map(|block_style| block_style.size)
+            .max()
+        {
+            Some(size) if size >= 32 => Some(0),
+            Some(size) if size >= 28 => Some(1),
+            _ => Some(2),
+        }
+    }
+
+    /// Whether the paragraph has an outline level of its own, e.g. to include body text in a table
+    /// of contents, while its paragraph style isn't a heading.
+    fn has_bare_outline_level(&self, styles: &HashMap<String, ParagraphStyle>) -> bool {
+        let Some(style) = &self.style else {
+            return false;
+        };
+        style.outline_lvl.is_some()
+            && style
+                .style_id
+                .as_ref()
+                .and_then(|style_id| styles.get(style_id))
+                .is_none_or(|paragraph_style| paragraph_style.outline_lvl.is_none())
+    }
+
+    /// The style of the paragraph, combined with the document style it refers to.
+    pub fn resolved_style(&self, styles: &HashMap<String, ParagraphStyle>) -> ParagraphStyle {
+        let mut style = self.style.clone().unwrap_or_default();
+
+        if let Some(style_id) = &style.style_id {
+            if let Some(doc_style) = styles.get(style_id) {
+                style.combine_with(doc_style);
+            }
+        };
+        style
+    }
+
+    /// Convert a docx::Paragraph to a MarkdownParagraph. The raw `w:p` element, when available,
+    /// supplies the content that docx-rust doesn't model, such as equations.
     fn from_paragraph(
         paragraph: &docx_rust::document::Paragraph,
-        docx: &docx_rust::Docx,
+        raw: Option<Node>,
+        context: &ParseContext,
     ) -> MarkdownParagraph {
         let mut markdown_paragraph = MarkdownParagraph::new();
         if let Some(paragraph_property) = &paragraph.property {
             let paragraph_style: ParagraphStyle = paragraph_property.into();
             markdown_paragraph.style = Some(paragraph_style);
         }
+        // docx-rust doesn't model contextual spacing, the paragraph direction or the vertical
+        // alignment and colors of the paragraph mark.
+        let raw_properties = w_children(raw, "pPr").first().copied();
+        let mark_properties = w_children(raw_properties, "rPr").first().copied();
+        if let Some(style) = markdown_paragraph
+            .style
+            .as_mut()
+            .and_then(|paragraph_style| paragraph_style.style.as_mut())
+        {
+            let vertical_align = package::vertical_align(mark_properties);
+            style.superscript = vertical_align.as_deref() == Some("superscript");
+            style.subscript = vertical_align.as_deref() == Some("subscript");
+            style.color = package::run_color(mark_properties);
+            style.highlight = package::run_highlight(mark_properties);
+        }
+        if package::is_on(raw_properties, "contextualSpacing") {
+            markdown_paragraph
+                .style
+                .get_or_insert_with(ParagraphStyle::new)
+                .contextual_spacing = Some(true);
+        }
+        if package::is_on(raw_properties, "bidi") {
+            markdown_paragraph
+                .style
+                .get_or_insert_with(ParagraphStyle::new)
+                .bidi = Some(true);
+        }
+        if let (Some(raw), true) = (raw, context.options.track_rsids) {
+            markdown_paragraph.rsid_r = raw.attribute((W_NS, "rsidR")).map(|r| r.to_string());
+            markdown_paragraph.rsid_r_pr = raw.attribute((W_NS, "rsidRPr")).map(|r| r.to_string());
+        }
+
+        // Raw inline elements are positioned by the number of runs that precede them.
+        let mut raw_inlines = vec![];
+        let mut raw_runs = vec![];
+        let mut raw_links = vec![];
+        if let Some(raw) = raw {
+            for child in raw.children() {
+                if is_element(&child, W_NS, "r") {
+                    raw_runs.push(child);
+                } else if is_element(&child, W_NS, "hyperlink") {
+                    raw_links.push(child);
+                } else if RAW_INLINES
+                    .iter()
+                    .any(|(ns, name)| is_element(&child, ns, name))
+                {
+                    raw_inlines.push((raw_runs.len(), child));
+                }
+            }
+        }
+        let mut raw_inlines = raw_inlines.into_iter().peekable();
+        let mut run_index = 0;
+        let mut raw_links = raw_links.into_iter();
+        // Cross-references become links to their bookmark, see `resolve_caption_references`.
+        let reference_fields = fields::reference_fields(&raw_runs);
+        // Page numbers have no markdown equivalent, so their result is replaced by a placeholder.
+        let page_number_fields = fields::page_number_fields(&raw_runs);
+        // The number of blocks and the length of the text of the last one when a field result
+        // starts, as the result may be merged into the text before it.
+        let mut field_start = (0, 0);
+
         for paragraph_content in &paragraph.content {
+            if let ParagraphContent::Run(_) = paragraph_content {
+                while let Some((_, node)) = raw_inlines.next_if(|(runs, _)| *runs <= run_index) {
+                    markdown_paragraph.push_raw_inline(node, context);
+                }
+                run_index += 1;
+            }
             match paragraph_content {
                 ParagraphContent::Run(run) => {
-                    let block_style = match &run.property {
-                        Some(character_property) => {
-                            let mut block_style = BlockStyle::new();
-                            if let Some(size) = &character_property.size {
-                                block_style.size = Some(size.value);
-                            }
-                            if character_property.bold.is_some() {
-                                block_style.bold = true;
-                            }
-                            if character_property.underline.is_some() {
-                                block_style.underline = true;
-                            }
-                            if character_property.italics.is_some()
-                                || character_property.emphasis.is_some()
-                            {
-                                block_style.italics = true;
-                            }
-                            if character_property.strike.is_some()
-                                || character_property.dstrike.is_some()
+                    let raw_run = raw_runs.get(run_index - 1).copied();
+                    if page_number_fields
+                        .iter()
+                        .any(|(_, end)| *end == run_index - 1)
+                    {
+                        markdown_paragraph.push_page_number();
+                    }
+                    if page_number_fields
+                        .iter()
+                        .any(|(start, end)| (*start..*end).contains(&(run_index - 1)))
+                    {
+                        continue;
+                    }
+                    if reference_fields
+                        .iter()
+                        .any(|field| field.start == run_index - 1)
+                    {
+                        let blocks = &markdown_paragraph.blocks;
+                        field_start = (blocks.len(), blocks.last().map_or(0, |b| b.text.len()));
+                    }
+                    markdown_paragraph.push_run(run, raw_run, context);
+                    if let Some(field) =
+                        reference_fields.iter().find(|field| field.end == run_index)
+                    {
+                        let (start, text_len) = field_start;
+                        let blocks = &mut markdown_paragraph.blocks;
+                        let mut result = blocks.split_off(start);
+                        if let Some(previous) = blocks.last_mut() {
+                            if previous.text_type == TextType::Text
+                                && previous.text.len() > text_len
                             {
-                                block_style.strike = true;
-                            }
-                            Some(block_style)
-                        }
-                        None => None,
-                    };
-
-                    let is_same_style = |style: &Option<BlockStyle>| style == &block_style;
-
-                    for run_content in &run.content {
-                        match run_content {
-                            RunContent::Text(text) => {
-                                let text = text.text.to_string();
-                                let mut could_extend_text = false;
-                                if let Some(prev_block) = markdown_paragraph.blocks.last_mut() {
-                                    if is_same_style(&prev_block.style)
-                                        && prev_block.text_type == TextType::Text
-                                    {
-                                        prev_block.text.push_str(&text);
-                                        could_extend_text = true
-                                    }
-                                };
-                                if !could_extend_text {
-                                    let text_block =
-                                        TextBlock::new(text, block_style.clone(), TextType::Text);
-                                    markdown_paragraph.blocks.push(text_block);
-                                }
+                                let text = previous.text.split_off(text_len);
+                                result.insert(
+                                    0,
+                                    TextBlock::new(text, previous.style.clone(), TextType::Text),
+                                );
                             }
-                            RunContent::Drawing(drawing) => {
-                                if let Some(inline) = &drawing.inline {
-                                    if let Some(graphic) = &inline
-                                        .graphic
-                                        .as_ref()
-                                        .and_then(|g| g.data.children.first())
-                                    {
-                                        let id = graphic.fill.blip.embed.to_string();
-                                        if let Some(relationships) = &docx.document_rels {
-                                            if let Some(target) = relationships.get_target(&id) {
-                                                let descr = match &inline.doc_property.descr {
-                                                    Some(descr) => descr.to_string(),
-                                                    None => "".to_string(),
-                                                };
-                                                let img_text =
-                                                    format!("![{}](./{})", descr, target);
-                                                let text_block =
-                                                    TextBlock::new(img_text, None, TextType::Image);
-                                                markdown_paragraph.blocks.push(text_block);
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                            _ => (),
                         }
+                        markdown_paragraph.push_reference(&result, &field.bookmark);
                     }
                 }
                 ParagraphContent::Link(link) => {
-                    let descr = link.content.as_ref().and_then(|r| r.content.first());
+                    // docx-rust only keeps the first run of a link, so the whole label, e.g. of
+                    // a link that spans the paragraph, is read from the raw link.
+                    let label = raw_links.next().map(|raw_link| {
+                        let mut label = MarkdownParagraph::new();
+                        label.push_raw_runs(raw_link, context);
+                        label.blocks
+                    });
+                    let descr = match &label {
+                        Some(blocks) if !blocks.is_empty() => Some(
+                            blocks
+                                .iter()
+                                .filter(|block| block.text_type == TextType::Text)
+                                .map(|block| block.text.as_str())
+                                .collect::<String>(),
+                        ),
+                        _ => match link.content.as_ref().and_then(|r| r.content.first()) {
+                            Some(RunContent::Text(descr)) => Some(descr.text.to_string()),
+                            _ => None,
+                        },
+                    };
+                    // Links are underlined and colored anyway.
+                    let style = label
+                        .as_deref()
+                        .and_then(BlockStyle::common)
+                        .map(|style| BlockStyle {
+                            underline: false,
+                            color: None,
+                            ..style
+                        })
+                        .filter(|style| *style != BlockStyle::new());
                     let target = match &link.anchor {
                         Some(anchor) => Some(format!("#{}", anchor)),
-                        None => match &link.id {
-                            Some(id) => match &docx.document_rels {
-                                Some(doc_relationships) => {
-                                    doc_relationships.relationships.iter().find_map(|r| {
-                                        if r.id == *id {
-                                            Some(r.target.to_string())
-                                        } else {
-                                            None
-                                        }
-                                    })
-                                }
-                                None => None,
-                            },
-                            None => None,
-                        },
+                        None => link
+                            .id
+                            .as_ref()
+                            .and_then(|id| context.target(id))
+                            .map(|target| target.to_string()),
                     };
-                    if let (Some(RunContent::Text(descr)), Some(target)) = (descr, target) {
-                        let link = format!("[{}]({})", descr.text, target);
-                        let text_block = TextBlock::new(link, None, TextType::Link);
+                    let target_mode = link
+                        .id
+                        .as_ref()
+                        .and_then(|id| context.target_modes.get(&**id))
+                        .cloned();
+                    let external = target_mode.as_deref() == Some("External");
+                    if let (Some(descr), Some(target)) = (descr, target) {
+                        let mut text_block = match &context.options.on_file_link {
+                            FileLinkHandling::Strip if is_file_link(&target, external) => {
+                                TextBlock::new(descr, style, TextType::Text)
+                            }
+                            FileLinkHandling::Rewrite(rewrite)
+                                if is_file_link(&target, external) =>
+                            {
+                                let link = format!("[{}]({})", descr, rewrite(&target));
+                                TextBlock::new(link, style, TextType::Link)
+                            }
+                            _ => {
+                                let link = format!("[{}]({})", descr, target);
+                                TextBlock::new(link, style, TextType::Link)
+                            }
+                        };
+                        if text_block.text_type == TextType::Link {
+                            text_block.rel_id = link.id.as_ref().map(|id| id.to_string());
+                            text_block.link_kind = Some(LinkKind::from_target(&target, external));
+                            text_block.target_mode = target_mode;
+                        }
                         markdown_paragraph.blocks.push(text_block);
                     }
                 }
@@ -455,43 +1339,625 @@ impl MarkdownParagraph {
                 _ => (),
             }
         }
+        for (_, node) in raw_inlines {
+            markdown_paragraph.push_raw_inline(node, context);
+        }
         markdown_paragraph
     }
+
+    /// Add the text and images of a run.
+    fn push_run(
+        &mut self,
+        run: &docx_rust::document::Run,
+        raw: Option<Node>,
+        context: &ParseContext,
+    ) {
+        let block_style = match &run.property {
+            Some(character_property) => {
+                let mut block_style = BlockStyle::new();
+                if let Some(size) = &character_property.size {
+                    block_style.size = Some(size.value);
+                }
+                if let Some(style_id) = &character_property.style_id {
+                    block_style.style_id = Some(style_id.value.to_string());
+                }
+                let raw_properties = w_children(raw, "rPr").first().copied();
+                block_style.font = package::run_font(raw_properties);
+                block_style.color = package::run_color(raw_properties);
+                block_style.highlight = package::run_highlight(raw_properties);
+                block_style.code = is_inline_code(
+                    block_style.font.as_deref(),
+                    package::run_shading(raw_properties).as_deref(),
+                );
+                block_style.rtl = package::is_on(raw_properties, "rtl");
+                let vertical_align = package::vertical_align(raw_properties);
+                block_style.superscript = vertical_align.as_deref() == Some("superscript");
+                block_style.subscript = vertical_align.as_deref() == Some("subscript");
+                apply_toggles(&mut block_style, character_property);
+                // Direct formatting goes on top of the character style.
+                match block_style
+                    .style_id
+                    .as_ref()
+                    .and_then(|style_id| context.character_styles.get(style_id))
+                {
+                    Some(character_style) => {
+                        let mut style = character_style.clone();
+                        style.combine_with(&block_style);
+                        Some(style)
+                    }
+                    None => Some(block_style),
+                }
+            }
+            None => None,
+        };
+
+        let (rsid_r, rsid_r_pr) = match raw {
+            Some(raw) if context.options.track_rsids => (
+                raw.attribute((W_NS, "rsidR")).map(|rsid| rsid.to_string()),
+                raw.attribute((W_NS, "rsidRPr"))
+                    .map(|rsid| rsid.to_string()),
+            ),
+            _ => (None, None),
+        };
+
+        // Runs are merged into the previous text block when nothing tells them apart: the
+        // previous block is text with the same style. Revision ids are only metadata, so a merged
+        // block keeps those of its first run. Breaks and drawings push blocks of their own, so
+        // that `foo<w:br/>bar` isn't `foobar`, and tabs are merged as `\t`.
+        let is_same_run = |block: &TextBlock| block.style == block_style;
+
+        // Whether a page or column break came before the text, which then starts on a new line.
+        let mut separated = raw.is_some_and(package::follows_break);
+        let raw_breaks = w_children(raw, "br");
+        let mut breaks = 0;
+        for run_content in &run.content {
+            match run_content {
+                RunContent::Break(_) => {
+                    let raw_break = raw_breaks.get(breaks);
+                    let clear =
+                        raw_break.and_then(|raw_break| raw_break.attribute((W_NS, "clear")));
+                    let break_type =
+                        raw_break.and_then(|raw_break| raw_break.attribute((W_NS, "type")));
+                    breaks += 1;
+                    if clear == Some("all") {
+                        self.blocks
+                            .push(TextBlock::new(String::new(), None, TextType::ClearBreak));
+                    } else if matches!(break_type, None | Some("textWrapping")) {
+                        self.blocks
+                            .push(TextBlock::new(String::new(), None, TextType::LineBreak));
+                    } else if break_type == Some("page") && self.blocks.is_empty() {
+                        // A page break before the text starts the paragraph on a new page.
+                        self.style
+                            .get_or_insert_with(ParagraphStyle::new)
+                            .page_break_before = Some(true);
+                    } else {
+                        // A page or column break in the text separates the text around it, but
+                        // ends the paragraph without a line break.
+                        separated = true;
+                    }
+                }
+                RunContent::Text(_) | RunContent::Tab(_) => {
+                    let text = match run_content {
+                        RunContent::Text(text) => text.text.to_string(),
+                        // A tab before the text is left out, e.g. between the number of a legal
+                        // list item and its text, which the list marker already separates.
+                        _ => {
+                            let has_text = self.blocks.iter().any(|block| {
+                                block.text_type == TextType::Text && !block.text.is_empty()
+                            });
+                            match has_text {
+                                true => "\t".to_string(),
+                                false => continue,
+                            }
+                        }
+                    };
+                    if std::mem::take(&mut separated) && !self.blocks.is_empty() {
+                        self.blocks
+                            .push(TextBlock::new(String::new(), None, TextType::LineBreak));
+                    }
+                    let mut could_extend_text = false;
+                    if let Some(prev_block) = self.blocks.last_mut() {
+                        if is_same_run(prev_block) && prev_block.text_type == TextType::Text {
+                            prev_block.text.push_str(&text);
+                            could_extend_text = true
+                        }
+                    };
+                    if !could_extend_text {
+                        let mut text_block =
+                            TextBlock::new(text, block_style.clone(), TextType::Text);
+                        text_block.rsid_r = rsid_r.clone();
+                        text_block.rsid_r_pr = rsid_r_pr.clone();
+                        self.blocks.push(text_block);
+                    }
+                }
+                RunContent::Drawing(drawing) => {
+                    let Some(inline) = &drawing.inline else {
+                        context.drop_content("a floating drawing".to_string());
+                        continue;
+                    };
+                    let Some(graphic) = &inline
+                        .graphic
+                        .as_ref()
+                        .and_then(|g| g.data.children.first())
+                    else {
+                        // Charts and SmartArt are added after the paragraph, which reports them
+                        // when their part can't be found.
+                        let is_added = raw.is_some_and(|raw| {
+                            raw.descendants().any(|node| {
+                                is_element(&node, C_NS, "chart")
+                                    || is_element(&node, DGM_NS, "relIds")
+                            })
+                        });
+                        if !is_added {
+                            context.drop_content("a drawing that is not a picture".to_string());
+                        }
+                        continue;
+                    };
+                    let id = graphic.fill.blip.embed.to_string();
+                    if let Some(target) = context.target(&id) {
+                        let descr = match &inline.doc_property.descr {
+                            Some(descr) => descr.to_string(),
+                            None => "".to_string(),
+                        };
+                        let img_text = format!("![{}](./{})", descr, target);
+                        let mut text_block = TextBlock::new(img_text, None, TextType::Image);
+                        text_block.rsid_r = rsid_r.clone();
+                        text_block.rsid_r_pr = rsid_r_pr.clone();
+                        text_block.image = raw.and_then(|raw| ImageProperties::from_run(raw, &id));
+                        text_block.rel_id = Some(id.clone());
+                        self.blocks.push(text_block);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        // Phonetic guides, e.g. furigana, with their base text.
+        let rubies = raw
+            .into_iter()
+            .flat_map(|raw| raw.children())
+            .filter(|node| is_element(node, W_NS, "ruby"));
+        for ruby in rubies {
+            let base = w_children(Some(ruby), "rubyBase");
+            let mut text_block = TextBlock::new(
+                base.first()
+                    .map(|base| text_content(*base))
+                    .unwrap_or_default(),
+                block_style.clone(),
+                TextType::Ruby,
+            );
+            let ruby_text = w_children(Some(ruby), "rt");
+            text_block.ruby = ruby_text.first().map(|ruby_text| text_content(*ruby_text));
+            self.blocks.push(text_block);
+        }
+
+        // Embedded (OLE) objects are shown by their preview image.
+        let objects = raw
+            .into_iter()
+            .flat_map(|raw| raw.children())
+            .filter(|node| is_element(node, W_NS, "object"));
+        for object in objects {
+            let Some(id) = object
+                .descendants()
+                .find(|node| is_element(node, V_NS, "imagedata"))
+                .and_then(|imagedata| imagedata.attribute((R_NS, "id")))
+            else {
+                context.drop_content("an embedded object without a preview image".to_string());
+                continue;
+            };
+            let Some(target) = context.target(id) else {
+                continue;
+            };
+            let prog_id = object
+                .descendants()
+                .find(|node| is_element(node, O_NS, "OLEObject"))
+                .and_then(|ole_object| ole_object.attribute("ProgID"));
+            let descr = match prog_id {
+                Some(prog_id) => format!("Embedded object: {prog_id}"),
+                None => "Embedded object".to_string(),
+            };
+            let mut text_block =
+                TextBlock::new(format!("![{descr}](./{target})"), None, TextType::Image);
+            text_block.rel_id = Some(id.to_string());
+            self.blocks.push(text_block);
+        }
+
+        // VML pictures, e.g. of older documents, instead of DrawingML.
+        let pictures = raw
+            .into_iter()
+            .flat_map(|raw| raw.children())
+            .filter(|node| is_element(node, W_NS, "pict"));
+        for picture in pictures {
+            let Some(imagedata) = picture
+                .descendants()
+                .find(|node| is_element(node, V_NS, "imagedata"))
+            else {
+                context.drop_content("a VML drawing that is not a picture".to_string());
+                continue;
+            };
+            let Some(id) = imagedata.attribute((R_NS, "id")) else {
+                continue;
+            };
+            let Some(target) = context.target(id) else {
+                continue;
+            };
+            let shape = imagedata
+                .parent()
+                .filter(|shape| is_element(shape, V_NS, "shape"));
+            let descr = shape
+                .and_then(|shape| shape.attribute("alt"))
+                .or_else(|| imagedata.attribute((O_NS, "title")))
+                .unwrap_or_default();
+            let mut text_block =
+                TextBlock::new(format!("![{descr}](./{target})"), None, TextType::Image);
+            text_block.rsid_r = rsid_r.clone();
+            text_block.rsid_r_pr = rsid_r_pr.clone();
+            text_block.image = shape.and_then(ImageProperties::from_vml_shape);
+            text_block.rel_id = Some(id.to_string());
+            self.blocks.push(text_block);
+        }
+
+        let references = raw
+            .into_iter()
+            .flat_map(|raw| raw.children())
+            .filter_map(|node| {
+                Some((
+                    NoteKind::from_reference(&node)?,
+                    node.attribute((W_NS, "id"))?,
+                ))
+            });
+        for (kind, id) in references {
+            let label = context.note_label(kind, id);
+            self.blocks
+                .push(TextBlock::new(label, None, TextType::NoteReference));
+        }
+
+        let comments = raw
+            .into_iter()
+            .flat_map(|raw| raw.children())
+            .filter(|node| is_element(node, W_NS, "commentReference"))
+            .filter_map(|node| context.comments.get(node.attribute((W_NS, "id"))?));
+        for comment in comments {
+            self.blocks.push(TextBlock::new(
+                comment.annotation(),
+                None,
+                TextType::CommentReference,
+            ));
+        }
+    }
+
+    /// Add an inline element that was taken from the raw paragraph XML.
+    fn push_raw_inline(&mut self, node: Node, context: &ParseContext) {
+        if is_element(&node, M_NS, "oMathPara") {
+            let text = node
+                .children()
+                .filter(|child| is_element(child, M_NS, "oMath"))
+                .map(math_latex)
+                .collect::<Vec<_>>()
+                .join(" ");
+            self.blocks
+                .push(TextBlock::new(text, None, TextType::MathBlock));
+        } else if is_element(&node, M_NS, "oMath") {
+            self.blocks
+                .push(TextBlock::new(math_latex(node), None, TextType::Math));
+        } else if is_element(&node, W_NS, "smartTag") || is_element(&node, W_NS, "customXml") {
+            // Wrappers around runs: their runs are parsed by docx-rust after all.
+            self.push_raw_runs(node, context);
+            if let Some(element) = node.attribute((W_NS, "element")) {
+                self.smart_tags.push(SmartTag {
+                    element: element.to_string(),
+                    uri: node.attribute((W_NS, "uri")).map(|uri| uri.to_string()),
+                    text: text_content(node),
+                });
+            }
+        } else if is_element(&node, W_NS, "fldSimple") {
+            let instruction = node.attribute((W_NS, "instr")).unwrap_or_default();
+            match fields::reference_target(instruction) {
+                _ if fields::is_page_number(instruction) => self.push_page_number(),
+                Some(bookmark) => {
+                    let mut result = MarkdownParagraph::new();
+                    result.push_raw_runs(node, context);
+                    self.push_reference(&result.blocks, bookmark);
+                }
+                None => self.push_raw_runs(node, context),
+            }
+        } else if is_element(&node, W_NS, "ins") {
+            match context.options.track_changes {
+                TrackChanges::AcceptAll => self.push_raw_runs(node, context),
+                TrackChanges::RejectAll => (),
+                TrackChanges::ShowMarkup => {
+                    self.push_markup("{++");
+                    self.push_raw_runs(node, context);
+                    self.push_markup("++}");
+                }
+                TrackChanges::HtmlMarkup => {
+                    self.push_markup(&html_revision_tag("ins", node));
+                    self.push_raw_runs(node, context);
+                    self.push_markup("</ins>");
+                }
+            }
+        } else if is_element(&node, W_NS, "del") {
+            match context.options.track_changes {
+                TrackChanges::AcceptAll => (),
+                TrackChanges::RejectAll => self.push_deleted_runs(node, context),
+                TrackChanges::ShowMarkup => {
+                    self.push_markup("{--");
+                    self.push_deleted_runs(node, context);
+                    self.push_markup("--}");
+                }
+                TrackChanges::HtmlMarkup => {
+                    self.push_markup(&html_revision_tag("del", node));
+                    self.push_deleted_runs(node, context);
+                    self.push_markup("</del>");
+                }
+            }
+        }
+    }
+
+    /// Add a cross-reference to a bookmark as an internal link, with the field result, e.g.
+    /// `Table 1`, as its label. A reference without a result is left out.
+    fn push_reference(&mut self, result: &[TextBlock], bookmark: &str) {
+        let label: String = result
+            .iter()
+            .filter(|block| block.text_type == TextType::Text)
+            .map(|block| block.text.as_str())
+            .collect();
+        if label.trim().is_empty() {
+            return;
+        }
+        let target = format!("#{bookmark}");
+        let mut text_block = TextBlock::new(
+            format!("[{}]({target})", label.trim()),
+            BlockStyle::common(result),
+            TextType::Link,
+        );
+        text_block.link_kind = Some(LinkKind::from_target(&target, false));
+        self.blocks.push(text_block);
+    }
+
+    /// Add the runs inside a raw wrapper element, such as a smart tag or an insertion.
+    fn push_raw_runs(&mut self, node: Node, context: &ParseContext) {
+        for child in node.children() {
+            if is_element(&child, W_NS, "r") {
+                if let Ok(run) = docx_rust::document::Run::from_str(outer_xml(child)) {
+                    self.push_run(&run, Some(child), context);
+                }
+            } else {
+                self.push_raw_inline(child, context);
+            }
+        }
+    }
+
+    /// Add the runs of a deletion, whose text is kept in `w:delText` instead of `w:t`.
+    fn push_deleted_runs(&mut self, node: Node, context: &ParseContext) {
+        for child in node.children() {
+            if is_element(&child, W_NS, "r") {
+                let xml = outer_xml(child)
+                    .replace("<w:delText", "<w:t")
+                    .replace("</w:delText>", "</w:t>");
+                if let Ok(run) = docx_rust::document::Run::from_str(&xml) {
+                    self.push_run(&run, Some(child), context);
+                }
+            }
+        }
+    }
+
+    /// Add a placeholder for the page number, e.g. of a `PAGE` field in a footer.
+    fn push_page_number(&mut self) {
+        self.blocks
+            .push(TextBlock::new("{page}".to_string(), None, TextType::Text));
+    }
+
+    /// Add markup around a tracked change, e.g. `{++` before an insertion.
+    fn push_markup(&mut self, markup: &str) {
+        self.blocks
+            .push(TextBlock::new(markup.to_string(), None, TextType::Text));
+    }
+}
+
+/// The opening HTML tag of a tracked change (`w:ins` or `w:del`), e.g. `<ins data-author="Ada">`.
+fn html_revision_tag(tag: &str, node: Node) -> String {
+    match node.attribute((W_NS, "author")) {
+        Some(author) => format!(r#"<{tag} data-author="{}">"#, author.replace('"', "&quot;")),
+        None => format!("<{tag}>"),
+    }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MarkdownDocument {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
+    /// Default document language (e.g. `en-US`), taken from the document defaults
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// The creators of the document (`dc:creator`)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub authors: Vec<String>,
+    /// The contributors to the document (`dc:contributor`)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub contributors: Vec<String>,
     pub content: Vec<MarkdownContent>,
     pub styles: HashMap<String, ParagraphStyle>,
     pub numberings: HashMap<isize, MarkdownNumbering>,
     #[serde(serialize_with = "serialize_images")]
     pub images: HashMap<String, Vec<u8>>,
+    /// Ranges that may be edited in a protected document
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub permissions: Vec<PermissionRange>,
+    /// Content that was found but could not be converted
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    /// The footnotes and endnotes, in the order they are referenced
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<MarkdownNote>,
+    /// The font family of text without a font of its own
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_font: Option<String>,
+    /// The default space after paragraphs, in twentieths of a point, e.g. 200 for 10pt
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_space_after: Option<usize>,
+    /// The tracked changes and comments, in document order, see
+    /// [`MarkdownDocument::revision_timeline`]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub revisions: Vec<RevisionEvent>,
+    /// The comments with the text they were left on, in the order of the comments part
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub comments: Vec<MarkdownComment>,
+    /// The page headers that the sections refer to, with a paragraph per header, in document
+    /// order. Page numbers are written as `{page}`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub headers: Vec<MarkdownParagraph>,
+    /// The page footers that the sections refer to, like [`MarkdownDocument::headers`]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub footers: Vec<MarkdownParagraph>,
+    /// The targets of the external hyperlink relationships of the main document part, see
+    /// [`MarkdownDocument::all_hyperlink_targets`]
+    #[serde(skip)]
+    pub hyperlink_targets: Vec<String>,
+    /// The number of levels that headings are moved up when they are rendered, see
+    /// [`MarkdownDocument::normalize_heading_levels`]
+    #[serde(skip)]
+    pub heading_offset: isize,
+}
+
+impl Default for MarkdownDocument {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl MarkdownDocument {
     pub fn new() -> Self {
         MarkdownDocument {
             title: None,
+            language: None,
+            authors: vec![],
+            contributors: vec![],
             content: vec![],
             styles: HashMap::new(),
             numberings: HashMap::new(),
             images: HashMap::new(),
+            permissions: vec![],
+            warnings: vec![],
+            notes: vec![],
+            default_font: None,
+            default_space_after: None,
+            revisions: vec![],
+            comments: vec![],
+            headers: vec![],
+            footers: vec![],
+            hyperlink_targets: vec![],
+            heading_offset: 0,
         }
     }
 
-    pub fn from_file<P: AsRef<Path>>(path: P) -> Option<Self> {
-        let file = File::open(path).ok()?;
-        Self::from_reader(file)
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, DocxParseError> {
+        Self::from_file_with(path, &MarkdownOptions::default())
+    }
+
+    /// Parse a docx file like [`MarkdownDocument::from_file`], without the reason why it failed.
+    pub fn from_file_opt<P: AsRef<Path>>(path: P) -> Option<Self> {
+        Self::from_file(path).ok()
+    }
+
+    pub fn from_reader<T: Read + Seek>(reader: T) -> Result<Self, DocxParseError> {
+        Self::from_reader_with(reader, &MarkdownOptions::default())
+    }
+
+    /// Parse a docx file from a reader that can't seek, such as a network stream. The whole
+    /// stream is read into memory first, as a zip file can't be read front to back.
+    pub fn from_read<R: Read>(mut reader: R) -> Result<Self, DocxParseError> {
+        let mut bytes = vec![];
+        reader.read_to_end(&mut bytes)?;
+        Self::from_reader(Cursor::new(bytes))
+    }
+
+    /// Only the text of a docx file, without building the document: the styles, numbering and
+    /// media are never read. The text is laid out like [`MarkdownDocument::to_plain_text`], except
+    /// that the alternative text of images, charts and SmartArt are left out.
+    pub fn extract_text<R: Read + Seek>(reader: R) -> Result<String, DocxParseError> {
+        text::extract_text(reader)
+    }
+
+    /// Parse a docx file, using the options that affect parsing, such as
+    /// [`MarkdownOptions::track_rsids`].
+    pub fn from_file_with<P: AsRef<Path>>(
+        path: P,
+        options: &MarkdownOptions,
+    ) -> Result<Self, DocxParseError> {
+        let file = File::open(path)?;
+        Self::from_reader_with(file, options)
     }
 
-    pub fn from_reader<T: Read + Seek>(reader: T) -> Option<Self> {
+    pub fn from_reader_with<T: Read + Seek>(
+        mut reader: T,
+        options: &MarkdownOptions,
+    ) -> Result<Self, DocxParseError> {
         let mut markdown_doc = MarkdownDocument::new();
 
-        let docx = DocxFile::from_reader(reader).ok()?;
-        let docx = docx.parse().ok()?;
+        let mut bytes = vec![];
+        reader.read_to_end(&mut bytes)?;
+        let mut package = Package::from_bytes(&bytes)?;
+        if let Some(part) = package.non_utf8_part() {
+            return Err(DocxParseError::Unsupported(format!("{part} is not UTF-8")));
+        }
+        if package.xml("word/document.xml").is_none() {
+            return Err(DocxParseError::MissingPart("word/document.xml".to_string()));
+        }
+        // docx-rust reads the zip file itself, so it gets a copy without byte order marks.
+        if package.strip_byte_order_marks() {
+            bytes = package.to_bytes()?;
+        }
+        let raw_document = package.parse("word/document.xml");
+        // The other parts are parsed once here too, for everything that is read from them.
+        let relationships = package.parse("word/_rels/document.xml.rels");
+        let relationships = relationships
+            .as_ref()
+            .map(roxmltree::Document::root_element);
+        let content_types = package.parse("[Content_Types].xml");
+        let core = package.parse("docProps/core.xml");
+        let core = core.as_ref().map(roxmltree::Document::root_element);
+        let raw_styles = package.parse("word/styles.xml");
+        let raw_styles = raw_styles.as_ref().map(roxmltree::Document::root_element);
+        let raw_numbering = package.parse("word/numbering.xml");
+        let raw_numbering = raw_numbering
+            .as_ref()
+            .map(roxmltree::Document::root_element);
+        let theme = package.parse("word/theme/theme1.xml");
+        let theme = theme.as_ref().map(roxmltree::Document::root_element);
+        let raw_body = raw_document.as_ref().and_then(package::body);
+        let raw_content = package::body_content(raw_body);
+        let alt_chunks = package::alt_chunks(raw_body);
+        if let Some(raw_body) = raw_body {
+            markdown_doc.permissions = permissions::permission_ranges(raw_body);
+            markdown_doc.revisions = revisions::revisions(raw_body);
+        }
+        markdown_doc.revisions.extend(revisions::comments(&package));
+        markdown_doc.comments = comments::parse_comments(&package, raw_body);
+
+        let docx = DocxFile::from_reader(Cursor::new(&bytes))
+            .map_err(|error| DocxParseError::Xml(error.to_string()))?;
+        let docx = docx
+            .parse()
+            .map_err(|error| DocxParseError::Xml(error.to_string()))?;
+        markdown_doc.hyperlink_targets = package::hyperlink_targets(relationships);
+        let context = ParseContext {
+            options,
+            targets: package::targets(relationships),
+            target_modes: package::target_modes(relationships),
+            content_types: content_types
+                .as_ref()
+                .map(roxmltree::Document::root_element),
+            note_references: RefCell::new(vec![]),
+            dropped: RefCell::new(vec![]),
+            character_styles: character_styles(raw_styles),
+            comments: markdown_doc
+                .comments
+                .iter()
+                .map(|comment| (comment.id.clone(), comment.clone()))
+                .collect(),
+        };
 
         if let Some(core) = &docx.core {
             if let Some(title) = match core {
@@ -504,6 +1970,24 @@ impl MarkdownDocument {
             }
         }
 
+        markdown_doc.authors = package::core_names(core, "creator");
+        markdown_doc.contributors = package::core_names(core, "contributor");
+        markdown_doc.default_font = package::default_font(raw_styles);
+        markdown_doc.default_space_after = package::default_space_after(raw_styles);
+
+        if let Some(lang) = docx
+            .styles
+            .default
+            .as_ref()
+            .and_then(|default| default.character.inner.as_ref())
+            .and_then(|character| character.lang.as_ref())
+            .and_then(|lang| lang.val.as_ref())
+        {
+            markdown_doc.language = Some(lang.to_string());
+        }
+
+        let numbering_fonts = package::numbering_fonts(raw_numbering);
+        let numbering_starts = package::numbering_starts(raw_numbering);
         if let Some(numbering) = &docx.numbering {
             numbering.numberings.iter().for_each(|n| {
                 if let Some(id) = n.num_id {
@@ -521,6 +2005,23 @@ impl MarkdownDocument {
                                     .level_text
                                     .as_ref()
                                     .map(|i| i.value.to_string()),
+                                levels: details
+                                    .levels
+                                    .iter()
+                                    .enumerate()
+                                    .map(|(i, level)| MarkdownNumberingLevel {
+                                        format: level
+                                            .number_format
+                                            .as_ref()
+                                            .map(|i| i.value.to_string()),
+                                        level_text: level
+                                            .level_text
+                                            .as_ref()
+                                            .map(|i| i.value.to_string()),
+                                        font: numbering_fonts.get(&(id, i)).cloned(),
+                                        start: numbering_starts.get(&(id, i)).copied(),
+                                    })
+                                    .collect(),
                             },
                         );
                     }
@@ -531,11 +2032,28 @@ impl MarkdownDocument {
         for (id, (MediaType::Image, media_data)) in &docx.media {
             markdown_doc.images.insert(id.clone(), media_data.to_vec());
         }
+        // docx-rust leaves out media without a known extension, which are kept when their content
+        // is an image.
+        for (name, data) in package.media() {
+            if !markdown_doc.images.contains_key(name) && image_mime_type(name, data).is_some() {
+                markdown_doc.images.insert(name.to_string(), data.to_vec());
+            }
+        }
 
+        // Without a styles part docx-rust uses empty styles, so only direct formatting applies.
+        if package.xml("word/styles.xml").is_none() {
+            markdown_doc
+                .warnings
+                .push("styles part not found, only direct formatting is used".to_string());
+        }
+        let contextual_spacing_styles = package::contextual_spacing_styles(raw_styles);
         for style in &docx.styles.styles {
             if let Some(StyleType::Paragraph) = style.ty {
                 if let Some(paragraph_property) = &style.paragraph {
-                    let paragraph_style: ParagraphStyle = paragraph_property.into();
+                    let mut paragraph_style: ParagraphStyle = paragraph_property.into();
+                    if contextual_spacing_styles.contains(&style.style_id.to_string()) {
+                        paragraph_style.contextual_spacing = Some(true);
+                    }
                     markdown_doc
                         .styles
                         .insert(style.style_id.to_string(), paragraph_style);
@@ -543,62 +2061,176 @@ impl MarkdownDocument {
             }
         }
 
-        for content in &docx.document.body.content {
+        let theme_colors = package::theme_colors(theme);
+        let body_len = docx.document.body.content.len();
+        for (i, content) in docx.document.body.content.iter().enumerate() {
+            for (_, chunk) in alt_chunks.iter().filter(|(position, _)| *position == i) {
+                markdown_doc.push_alt_chunk(*chunk, &context, &package);
+            }
+            let raw = raw_content.get(i).copied();
             match content {
                 Paragraph(paragraph) => {
-                    let markdown_paragraph = MarkdownParagraph::from_paragraph(paragraph, &docx);
-                    if !markdown_paragraph.blocks.is_empty() {
+                    let markdown_paragraph =
+                        MarkdownParagraph::from_paragraph(paragraph, raw, &context);
+                    if markdown_paragraph.is_excluded(options) {
+                        continue;
+                    }
+                    if !markdown_paragraph.blocks.is_empty() || options.keep_empty_paragraphs {
                         markdown_doc
                             .content
-                            .push(MarkdownContent::Paragraph(markdown_paragraph));
+                            .push(MarkdownContent::Paragraph(Box::new(markdown_paragraph)));
+                    }
+                    // Charts keep their data in a separate part, which is added as a table.
+                    let charts = raw
+                        .into_iter()
+                        .flat_map(|raw| raw.descendants())
+                        .filter(|node| is_element(node, C_NS, "chart"))
+                        .filter_map(|node| node.attribute((R_NS, "id")));
+                    for id in charts {
+                        match context
+                            .target(id)
+                            .and_then(|target| package.xml(&part_path(target)))
+                            .and_then(MarkdownChart::from_xml)
+                        {
+                            Some(chart) => markdown_doc.content.push(MarkdownContent::Chart(chart)),
+                            None => context.drop_content(format!("chart {id}: part not found")),
+                        }
+                    }
+                    // SmartArt keeps its text in a separate data part, which is added as a list.
+                    let diagrams = raw
+                        .into_iter()
+                        .flat_map(|raw| raw.descendants())
+                        .filter(|node| is_element(node, DGM_NS, "relIds"))
+                        .filter_map(|node| node.attribute((R_NS, "dm")));
+                    for id in diagrams {
+                        match context
+                            .target(id)
+                            .and_then(|target| package.xml(&part_path(target)))
+                            .and_then(MarkdownSmartArt::from_xml)
+                        {
+                            Some(smart_art) => markdown_doc
+                                .content
+                                .push(MarkdownContent::SmartArt(smart_art)),
+                            None => {
+                                context.drop_content(format!("SmartArt {id}: data part not found"))
+                            }
+                        }
                     }
                 }
                 Table(table) => {
+                    let raw_rows = w_children(raw, "tr");
                     let rows_columns: MarkdownTable = table
                         .rows
                         .iter()
-                        .map(|row| {
+                        .enumerate()
+                        .map(|(r, row)| {
                             let is_header = match &row.property.table_header {
+                                // A bare `w:tblHeader` is on.
                                 Some(table_header) => {
-                                    matches!(table_header.value, Some(OnOffOnlyType::On))
+                                    !matches!(table_header.value, Some(OnOffOnlyType::Off))
                                 }
                                 None => false,
                             };
-                            let cells: Vec<Vec<MarkdownParagraph>> = row
+                            let raw_cells = w_children(raw_rows.get(r).copied(), "tc");
+                            let cells: Vec<(MarkdownTableCell, Option<String>)> = row
                                 .cells
                                 .iter()
                                 .filter_map(|row_content| match row_content {
-                                    TableRowContent::TableCell(cell) => {
-                                        let cells: Vec<MarkdownParagraph> = cell
-                                            .content
-                                            .iter()
-                                            .filter_map(|content| match content {
-                                                TableCellContent::Paragraph(paragraph) => {
-                                                    Some(MarkdownParagraph::from_paragraph(
-                                                        paragraph, &docx,
-                                                    ))
-                                                } // _ => None,
-                                            })
-                                            .collect();
-                                        if !cells.is_empty() {
-                                            Some(cells)
-                                        } else {
-                                            None
-                                        }
-                                    }
+                                    TableRowContent::TableCell(cell) => Some(cell),
                                     _ => None,
                                 })
+                                .enumerate()
+                                .filter_map(|(c, cell)| {
+                                    let raw_cell = raw_cells.get(c).copied();
+                                    let raw_paragraphs = w_children(raw_cell, "p");
+                                    let paragraphs: Vec<MarkdownParagraph> = cell
+                                        .content
+                                        .iter()
+                                        .enumerate()
+                                        .map(|(p, content)| match content {
+                                            TableCellContent::Paragraph(paragraph) => {
+                                                MarkdownParagraph::from_paragraph(
+                                                    paragraph,
+                                                    raw_paragraphs.get(p).copied(),
+                                                    &context,
+                                                )
+                                            }
+                                        })
+                                        .collect();
+                                    if !paragraphs.is_empty() {
+                                        let mut cell = MarkdownTableCell::new(paragraphs);
+                                        cell.grid_span =
+                                            package::grid_span(raw_cell).filter(|span| *span > 1);
+                                        cell.v_merge = match package::vertical_merge(raw_cell) {
+                                            Some(merge) if merge == "restart" => {
+                                                Some(VMergeKind::Restart)
+                                            }
+                                            Some(_) => Some(VMergeKind::Continue),
+                                            None => None,
+                                        };
+                                        let justification =
+                                            package::justification(raw_paragraphs.first().copied());
+                                        cell.alignment = justification
+                                            .and_then(|jc| CellAlignment::from_justification(&jc));
+                                        Some((
+                                            cell,
+                                            package::cell_background(raw_cell, &theme_colors),
+                                        ))
+                                    } else {
+                                        None
+                                    }
+                                })
+                                .collect();
+                            let continued = cells
+                                .iter()
+                                .enumerate()
+                                .filter(|(_, (cell, _))| cell.v_merge == Some(VMergeKind::Continue))
+                                .map(|(c, _)| c)
                                 .collect();
-                            MarkdownTableRow { is_header, cells }
+                            let backgrounds = cells
+                                .iter()
+                                .enumerate()
+                                .filter_map(|(c, (_, background))| Some((c, background.clone()?)))
+                                .collect();
+                            let cells = cells.into_iter().map(|(cell, _)| cell).collect();
+                            MarkdownTableRow {
+                                is_header,
+                                cells,
+                                continued,
+                                backgrounds,
+                            }
                         })
                         .collect();
 
-                    markdown_doc
-                        .content
-                        .push(MarkdownContent::Table(rows_columns));
+                    match numbered_equations(&rows_columns) {
+                        Some(equations) => markdown_doc.content.extend(
+                            equations
+                                .into_iter()
+                                .map(|equation| MarkdownContent::Paragraph(Box::new(equation))),
+                        ),
+                        None => markdown_doc
+                            .content
+                            .push(MarkdownContent::Table(rows_columns)),
+                    }
                 }
                 Sdt(_) => {
-                    // println!("Sdt");
+                    // A table of contents is generated from the headings anyway, and a control
+                    // without text has nothing to lose.
+                    let gallery = w_children(raw, "sdtPr")
+                        .iter()
+                        .flat_map(|properties| properties.descendants())
+                        .find(|node| is_element(node, W_NS, "docPartGallery"))
+                        .and_then(|gallery| gallery.attribute((W_NS, "val")));
+                    let has_text = raw
+                        .into_iter()
+                        .flat_map(|raw| raw.descendants())
+                        .any(|node| {
+                            is_element(&node, W_NS, "t")
+                                && node.text().is_some_and(|text| !text.is_empty())
+                        });
+                    if has_text && gallery != Some("Table of Contents") {
+                        context.drop_content("a block content control (w:sdt)".to_string());
+                    }
                 }
                 SectionProperty(_sp) => {
                     // println!("SectionProperty: {:?}", sp);
@@ -611,202 +2243,3306 @@ impl MarkdownDocument {
                 }
             }
         }
+        for (_, chunk) in alt_chunks
+            .iter()
+            .filter(|(position, _)| *position >= body_len)
+        {
+            markdown_doc.push_alt_chunk(*chunk, &context, &package);
+        }
 
-        Some(markdown_doc)
-    }
-
-    pub fn to_json(&self, pretty: bool) -> Option<String> {
-        if pretty {
-            serde_json::to_string_pretty(self).ok()
-        } else {
-            serde_json::to_string(self).ok()
+        if let Some(raw_body) = raw_body {
+            (markdown_doc.headers, markdown_doc.footers) =
+                header_footer::parse_headers_footers(&package, raw_body, &context);
         }
-    }
 
-    pub fn to_markdown(&self, export_images: bool) -> String {
-        let mut markdown = String::new();
+        let references = context.note_references.take();
+        markdown_doc.notes = notes::parse_notes(&package, &references, &context);
+        markdown_doc.resolve_caption_references();
 
-        if let Some(title) = &self.title {
-            markdown += &format!("# {}\n\n", title);
+        let dropped = context.dropped.take();
+        if options.strict && !dropped.is_empty() {
+            return Err(DocxParseError::Unsupported(format!(
+                "dropped {}",
+                dropped.join(", ")
+            )));
         }
+        markdown_doc.warnings.extend(
+            dropped
+                .into_iter()
+                .map(|warning| format!("Dropped {warning}")),
+        );
 
-        let mut numberings: HashMap<isize, usize> = HashMap::new();
+        Ok(markdown_doc)
+    }
 
-        for (index, content) in self.content.iter().enumerate() {
-            match content {
-                MarkdownContent::Paragraph(paragraph) => {
-                    markdown += &paragraph.to_markdown(&self.styles, &mut numberings, self);
-                    markdown += "\n";
-                }
-                MarkdownContent::Table(table) => {
-                    let table_with_simple_cells: Vec<(bool, Vec<String>)> = table
-                        .iter()
-                        .map(|MarkdownTableRow { is_header, cells }| {
-                            let row_content: &Vec<String> = &cells
-                                .iter()
-                                .map(|cell| {
-                                    let cell_content = &cell.iter().enumerate().fold(
-                                        "".to_string(),
-                                        |mut content, (i, paragraph)| {
-                                            let paragraph_as_markdown = &paragraph.to_markdown(
-                                                &self.styles,
-                                                &mut numberings,
-                                                self,
-                                            );
-                                            if i + 1 < cell.len() {
-                                                content +=
-                                                    &format!("{}<br/>", paragraph_as_markdown);
-                                            } else {
-                                                content += paragraph_as_markdown;
-                                            }
-                                            content
-                                        },
-                                    );
-                                    cell_content.clone()
-                                })
-                                .collect();
-                            (*is_header, row_content.clone())
-                        })
-                        .collect();
-                    let column_lengths = max_lengths_per_column(&table_with_simple_cells, 3);
-                    let divider = &table_row_to_markdown(
-                        &column_lengths,
-                        &column_lengths.iter().map(|i| "-".repeat(*i)).collect(),
-                    );
-                    let table = &table_with_simple_cells.iter().enumerate().fold(
-                        "".to_string(),
-                        |mut acc, (i, (is_header, row))| {
-                            let markdown_row = &table_row_to_markdown(&column_lengths, row);
-                            if i == 0 {
-                                if *is_header {
-                                    acc.push_str(markdown_row);
-                                    acc.push_str(divider);
-                                } else {
-                                    acc.push_str(&table_row_to_markdown(
-                                        &column_lengths,
-                                        &column_lengths.iter().map(|_| "".to_string()).collect(),
-                                    ));
-                                    acc.push_str(divider);
-                                    acc.push_str(markdown_row);
-                                }
-                            } else {
-                                acc.push_str(markdown_row);
-                            }
-                            if i == table_with_simple_cells.len() {
-                                acc.push('\n');
-                            }
-                            acc
-                        },
-                    );
-                    markdown += table;
+    /// Point cross-references to figure and table captions at an anchor named after the caption
+    /// label, e.g. `[Table 1](#table-1)` instead of `#_Ref1`, and label them with the caption label,
+    /// as the result of a page reference is a page number.
+    fn resolve_caption_references(&mut self) {
+        let mut captions: HashMap<String, (String, String)> = HashMap::new();
+        for paragraph in self.paragraphs() {
+            let style = paragraph.resolved_style(&self.styles);
+            if style.style_id.as_deref() != Some("Caption") {
+                continue;
+            }
+            let text = paragraph.to_plain_text(&PlainTextOptions::default());
+            let label = caption_label(&text);
+            if label.is_empty() {
+                continue;
+            }
+            for block in &paragraph.blocks {
+                if let Some(name) = bookmark_name(block) {
+                    captions.insert(name.to_string(), (label.to_string(), slugify(label)));
                 }
-            };
-            if index != self.content.len() - 1 {
-                markdown += "\n";
             }
         }
-
-        if export_images {
-            for (image, data) in &self.images {
-                match save_image_to_file(image, data) {
-                    Ok(_) => (),
-                    Err(err) => eprintln!("{err}"),
+        if captions.is_empty() {
+            return;
+        }
+        for paragraph in self.paragraphs_mut() {
+            for block in &mut paragraph.blocks {
+                let text = match block.text_type {
+                    TextType::Link => link_target(&block.text)
+                        .strip_prefix('#')
+                        .and_then(|bookmark| captions.get(bookmark))
+                        .map(|(label, slug)| format!("[{label}](#{slug})")),
+                    TextType::BookmarkLink => bookmark_name(block)
+                        .and_then(|name| captions.get(name))
+                        .map(|(_, slug)| format!(r#"<a name="{slug}"></a>"#)),
+                    _ => None,
                 };
+                if let Some(text) = text {
+                    block.text = text;
+                }
             }
         }
-
-        markdown
     }
-}
 
-#[derive(Debug, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub enum MarkdownContent {
-    Paragraph(MarkdownParagraph),
-    Table(MarkdownTable),
-}
+    /// Add the content of a `w:altChunk`: the text of HTML and plain text chunks, or the content
+    /// of an embedded docx. Other chunks are recorded as dropped content.
+    fn push_alt_chunk(&mut self, chunk: Node, context: &ParseContext, package: &Package) {
+        let Some(id) = chunk.attribute((R_NS, "id")) else {
+            return;
+        };
+        let Some(part) = context.target(id).map(part_path) else {
+            context.drop_content(format!("altChunk {id}: relationship not found"));
+            return;
+        };
+        match AltChunk::from_part(package, context.content_types, &part) {
+            Some(AltChunk::Paragraphs(paragraphs)) => {
+                for text in paragraphs {
+                    let mut paragraph = MarkdownParagraph::new();
+                    paragraph
+                        .blocks
+                        .push(TextBlock::new(text, None, TextType::Text));
+                    self.content
+                        .push(MarkdownContent::Paragraph(Box::new(paragraph)));
+                }
+            }
+            Some(AltChunk::Document(data)) => {
+                match MarkdownDocument::from_reader_with(Cursor::new(data), context.options) {
+                    Ok(embedded) => {
+                        for (id, style) in embedded.styles {
+                            self.styles.entry(id).or_insert(style);
+                        }
+                        for (id, numbering) in embedded.numberings {
+                            self.numberings.entry(id).or_insert(numbering);
+                        }
+                        for (id, image) in embedded.images {
+                            self.images.entry(id).or_insert(image);
+                        }
+                        self.content.extend(embedded.content);
+                        self.warnings.extend(embedded.warnings);
+                    }
+                    Err(error) => context.drop_content(format!(
+                        "altChunk {part}: embedded document could not be parsed ({error})"
+                    )),
+                }
+            }
+            Some(AltChunk::Unsupported(content_type)) => context.drop_content(format!(
+                "altChunk {part}: unsupported content type '{content_type}'"
+            )),
+            None => context.drop_content(format!("altChunk {part}: part not found")),
+        }
+    }
 
-pub type MarkdownTable = Vec<MarkdownTableRow>;
+    /// Shift all headings up, so the shallowest heading level that is used becomes `#`, e.g. when
+    /// a document starts with Heading 2. The relative hierarchy of the headings is kept.
+    pub fn normalize_heading_levels(&mut self) {
+        self.normalize_heading_levels_with(&MarkdownOptions::default());
+    }
 
-#[derive(Debug, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct MarkdownTableRow {
-    is_header: bool,
-    cells: Vec<MarkdownTableCell>,
-}
+    /// Like [`MarkdownDocument::normalize_heading_levels`], for the headings as they are rendered
+    /// with `options`, e.g. those of [`MarkdownOptions::style_overrides`].
+    pub fn normalize_heading_levels_with(&mut self, options: &MarkdownOptions) {
+        self.heading_offset = self
+            .content
+            .iter()
+            .filter_map(|content| match content {
+                MarkdownContent::Paragraph(paragraph) if !paragraph.is_excluded(options) => {
+                    paragraph.rendered_heading_level(&self.styles, options)
+                }
+                _ => None,
+            })
+            .min()
+            .unwrap_or(0);
+    }
 
-pub type MarkdownTableCell = Vec<MarkdownParagraph>;
+    /// The heading level (0 for `#`) that a paragraph is rendered at, after
+    /// [`MarkdownDocument::normalize_heading_levels`].
+    fn paragraph_heading_level(
+        &self,
+        paragraph: &MarkdownParagraph,
+        options: &MarkdownOptions,
+    ) -> Option<isize> {
+        paragraph
+            .rendered_heading_level(&self.styles, options)
+            .map(|level| (level - self.heading_offset).max(0))
+    }
 
-#[cfg(test)]
-mod tests {
-    use std::fs;
+    /// A document with only the selected content, e.g. `doc.slice(2..5)`. The styles,
+    /// numberings, images and metadata are kept.
+    pub fn slice<R: RangeBounds<usize>>(&self, range: R) -> MarkdownDocument {
+        let start = match range.start_bound() {
+            Bound::Included(start) => *start,
+            Bound::Excluded(start) => start + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(end) => end + 1,
+            Bound::Excluded(end) => *end,
+            Bound::Unbounded => self.content.len(),
+        };
+        let end = end.min(self.content.len());
+        MarkdownDocument {
+            title: self.title.clone(),
+            language: self.language.clone(),
+            authors: self.authors.clone(),
+            contributors: self.contributors.clone(),
+            content: self.content[start.min(end)..end].to_vec(),
+            styles: self.styles.clone(),
+            numberings: self.numberings.clone(),
+            images: self.images.clone(),
+            permissions: self.permissions.clone(),
+            warnings: self.warnings.clone(),
+            notes: self.notes.clone(),
+            default_font: self.default_font.clone(),
+            default_space_after: self.default_space_after,
+            revisions: self.revisions.clone(),
+            comments: self.comments.clone(),
+            headers: self.headers.clone(),
+            footers: self.footers.clone(),
+            hyperlink_targets: self.hyperlink_targets.clone(),
+            heading_offset: self.heading_offset,
+        }
+    }
 
-    // Import necessary items
-    use super::*;
+    /// The tracked changes and comments sorted by date, e.g. for an audit trail. Events without a
+    /// date come last, and events with the same date keep their document order.
+    pub fn revision_timeline(&self) -> Vec<RevisionEvent> {
+        let mut timeline = self.revisions.clone();
+        timeline.sort_by(|a, b| match (&a.date, &b.date) {
+            (Some(a), Some(b)) => a.cmp(b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        });
+        timeline
+    }
 
-    #[test]
-    fn test_headers() {
-        let markdown_pandoc = fs::read_to_string("./test/headers.md").unwrap();
-        let markdown_doc = MarkdownDocument::from_file("./test/headers.docx").unwrap();
-        let markdown = markdown_doc.to_markdown(false);
-        assert_eq!(markdown_pandoc, markdown);
+    /// An image block with its image as a base64 data URL, see
+    /// [`MarkdownOptions::base64_images`], or the block as it is when the image isn't in the
+    /// package.
+    fn inline_image(&self, block: &TextBlock) -> TextBlock {
+        let target = link_target(&block.text);
+        // The images are keyed like the targets, e.g. `media/image1.png`.
+        let name = target.trim_start_matches("./");
+        let mut block = block.clone();
+        if let Some(data) = self.images.get(name) {
+            block.text = format!("![{}]({})", link_text(&block.text), data_url(name, data));
+        }
+        block
     }
 
-    #[test]
-    fn test_bullets() {
-        let markdown_pandoc = fs::read_to_string("./test/lists.md").unwrap();
-        let markdown_doc = MarkdownDocument::from_file("./test/lists.docx").unwrap();
-        let markdown = markdown_doc.to_markdown(false);
-        assert_eq!(markdown_pandoc, markdown);
+    /// Every external hyperlink target of the document, including those of relationships that no
+    /// hyperlink refers to anymore, e.g. for a scan of all URLs in the package.
+    pub fn all_hyperlink_targets(&self) -> Vec<String> {
+        self.hyperlink_targets.clone()
     }
 
-    #[test]
-    fn test_images() {
-        let markdown_pandoc = fs::read_to_string("./test/image.md").unwrap();
-        let markdown_doc = MarkdownDocument::from_file("./test/image.docx").unwrap();
-        let markdown = markdown_doc.to_markdown(false);
-        assert_eq!(markdown_pandoc, markdown);
+    /// The heading with the given text and everything below it, up to the next heading of the
+    /// same or a higher level.
+    pub fn under_heading(&self, heading_text: &str) -> Option<MarkdownDocument> {
+        let markdown_options = MarkdownOptions::default();
+        let heading_level = |content: &MarkdownContent| match content {
+            MarkdownContent::Paragraph(paragraph) => {
+                paragraph.rendered_heading_level(&self.styles, &markdown_options)
+            }
+            _ => None,
+        };
+        let options = PlainTextOptions::default();
+        let (start, level) = self.content.iter().enumerate().find_map(|(i, content)| {
+            let level = heading_level(content)?;
+            match content {
+                MarkdownContent::Paragraph(paragraph)
+                    if paragraph.to_plain_text(&options).trim() == heading_text.trim() =>
+                {
+                    Some((i, level))
+                }
+                _ => None,
+            }
+        })?;
+        let end = self.content[start + 1..]
+            .iter()
+            .position(|content| heading_level(content).is_some_and(|other| other <= level))
+            .map_or(self.content.len(), |position| start + 1 + position);
+        Some(self.slice(start..end))
     }
 
-    #[test]
-    fn test_links() {
-        let markdown_pandoc = fs::read_to_string("./test/links.md").unwrap();
-        let markdown_doc = MarkdownDocument::from_file("./test/links.docx").unwrap();
-        let markdown = markdown_doc.to_markdown(false);
-        assert_eq!(markdown_pandoc, markdown);
+    /// The document language, used for spelled-out list numbering.
+    pub fn language(&self) -> Option<&str> {
+        self.language.as_deref()
     }
 
-    #[test]
-    fn test_tables() {
-        let markdown_pandoc = fs::read_to_string("./test/tables.md").unwrap();
-        let markdown_doc = MarkdownDocument::from_file("./test/tables.docx").unwrap();
-        let markdown = markdown_doc.to_markdown(false);
+    pub fn to_json(&self, pretty: bool) -> Option<String> {
+        if pretty {
+            serde_json::to_string_pretty(self).ok()
+        } else {
+            serde_json::to_string(self).ok()
+        }
+    }
+
+    /// Convert to JSON, using the options that affect JSON, such as
+    /// [`MarkdownOptions::image_json_mode`].
+    pub fn to_json_with(&self, pretty: bool, options: &MarkdownOptions) -> Option<String> {
+        if options.image_json_mode == ImageJsonMode::Base64
+            && options.font_size_json == FontSizeJson::HalfPoints
+        {
+            return self.to_json(pretty);
+        }
+        let mut json = serde_json::to_value(self).ok()?;
+        let object = json.as_object_mut()?;
+        match options.image_json_mode {
+            ImageJsonMode::Base64 => (),
+            ImageJsonMode::Paths => {
+                let images = self
+                    .images
+                    .keys()
+                    .map(|id| {
+                        let path = id.strip_prefix("word/").unwrap_or(id);
+                        (id.clone(), serde_json::Value::String(format!("./{path}")))
+                    })
+                    .collect::<serde_json::Map<_, _>>();
+                object.insert("images".to_string(), images.into());
+            }
+            ImageJsonMode::Omit => {
+                object.remove("images");
+            }
+        }
+        if options.font_size_json != FontSizeJson::HalfPoints {
+            font_sizes_to_json(&mut json, options.font_size_json);
+        }
+        if pretty {
+            serde_json::to_string_pretty(&json).ok()
+        } else {
+            serde_json::to_string(&json).ok()
+        }
+    }
+
+    pub fn to_markdown(&self, export_images: bool) -> String {
+        self.to_markdown_with(&MarkdownOptions {
+            export_images,
+            ..Default::default()
+        })
+    }
+
+    /// Convert the document to markdown like [`MarkdownDocument::to_markdown_with`], but fail
+    /// when an image can't be written instead of printing the error, e.g. when
+    /// [`MarkdownOptions::image_dir`] can't be created.
+    pub fn to_markdown_with_options(&self, options: &MarkdownOptions) -> io::Result<String> {
+        let mut markdown = vec![];
+        self.stream_markdown(&mut markdown, options)?;
+        Ok(String::from_utf8(markdown).expect("the markdown is UTF-8"))
+    }
+
+    pub fn to_markdown_with(&self, options: &MarkdownOptions) -> String {
+        let mut markdown = vec![];
+        self.write_markdown(&mut markdown, options)
+            .expect("writing to a Vec doesn't fail");
+        if let Err(err) = self.export_images(options) {
+            eprintln!("{err}");
+        }
+        String::from_utf8(markdown).expect("the markdown is UTF-8")
+    }
+
+    /// The content of the document, in order, e.g. to process a block at a time.
+    pub fn blocks_iter(&self) -> impl Iterator<Item = &MarkdownContent> {
+        self.content.iter()
+    }
+
+    /// Write the document as markdown like [`MarkdownDocument::to_markdown_with`], a block at a
+    /// time, so the whole markdown is never held in memory. Unlike `to_markdown_with`, an image
+    /// that can't be exported is an error.
+    pub fn stream_markdown<W: Write>(
+        &self,
+        markdown: &mut W,
+        options: &MarkdownOptions,
+    ) -> io::Result<()> {
+        self.write_markdown(markdown, options)?;
+        self.export_images(options)
+    }
+
+    /// Save the images of the document when [`MarkdownOptions::export_images`] is set, to the
+    /// paths that the markdown refers to them by.
+    fn export_images(&self, options: &MarkdownOptions) -> io::Result<()> {
+        if options.export_images {
+            for (image, data) in &self.images {
+                let path = options.image_path(image).unwrap_or_else(|| image.clone());
+                save_image_to_file(&path, data)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the markdown of the document, without exporting the images.
+    fn write_markdown<W: Write>(
+        &self,
+        markdown: &mut W,
+        options: &MarkdownOptions,
+    ) -> io::Result<()> {
+        let mut numberings: HashMap<(isize, isize), usize> = HashMap::new();
+
+        if let (true, Some(header)) = (options.headers_footers, self.headers.first()) {
+            let header = header.to_markdown(&self.styles, &mut numberings, self, options);
+            write!(markdown, "{header}\n\n---\n\n")?;
+        }
+
+        if let Some(title) = &self.title {
+            writeln!(markdown, "# {}", title)?;
+        }
+
+        // The indent and content offset of the last list item at each level.
+        let mut list_items: Vec<(usize, usize)> = vec![];
+        // Every 24pt of space after paragraphs adds a blank line.
+        let extra_blank_lines = match (options.blank_lines_from_spacing, self.default_space_after) {
+            (true, Some(space_after)) => space_after / 480,
+            _ => 0,
+        };
+
+        let content: Vec<&MarkdownContent> = self
+            .content
+            .iter()
+            .filter(|content| match content {
+                MarkdownContent::Paragraph(paragraph) => !paragraph.is_excluded(options),
+                _ => true,
+            })
+            .collect();
+        if self.title.is_some() && !content.is_empty() {
+            writeln!(markdown)?;
+        }
+        for (index, block_content) in content.iter().copied().enumerate() {
+            let block = match block_content {
+                MarkdownContent::Paragraph(paragraph) => {
+                    let mut paragraph_markdown =
+                        paragraph.to_markdown(&self.styles, &mut numberings, self, options);
+                    let style = paragraph.resolved_style(&self.styles);
+                    let level = style
+                        .numbering
+                        .as_ref()
+                        .and_then(|numbering| numbering.indent_level);
+                    match level {
+                        Some(level) if !paragraph_markdown.starts_with('#') => {
+                            paragraph_markdown = nest_list_item(
+                                paragraph_markdown,
+                                level.max(0) as usize,
+                                &mut list_items,
+                                options.list_indent,
+                            );
+                        }
+                        _ => list_items.clear(),
+                    }
+                    // Headings and code blocks are kept on one line each.
+                    let keep_lines = paragraph.is_code_block(&self.styles, options)
+                        || paragraph
+                            .rendered_heading_level(&self.styles, options)
+                            .is_some();
+                    match options.wrap_width {
+                        Some(width) if !keep_lines => {
+                            // Continuation lines of list items line up with the item text.
+                            let indent = match style.numbering {
+                                Some(_) => {
+                                    let marker = paragraph_markdown.trim_start().split(' ').next();
+                                    let leading = paragraph_markdown.len()
+                                        - paragraph_markdown.trim_start().len();
+                                    leading + marker.map_or(0, |marker| marker.chars().count() + 1)
+                                }
+                                None => 0,
+                            };
+                            let lines: Vec<String> = paragraph_markdown
+                                .split('\n')
+                                .map(|line| match line.strip_suffix("  ") {
+                                    // Keep hard line breaks.
+                                    Some(line) => wrap_markdown(line, width, indent) + "  ",
+                                    None => wrap_markdown(line, width, indent),
+                                })
+                                .collect();
+                            lines.join("\n")
+                        }
+                        _ => paragraph_markdown,
+                    }
+                }
+                MarkdownContent::Table(table) => {
+                    self.table_to_markdown(table, &mut numberings, options)
+                }
+                MarkdownContent::Chart(chart) => {
+                    let name = chart.title.as_ref().unwrap_or(&chart.chart_type);
+                    format!(
+                        "*Chart: {}*\n\n{}",
+                        name,
+                        self.table_to_markdown(&chart.table, &mut numberings, options)
+                    )
+                }
+                MarkdownContent::SmartArt(smart_art) => {
+                    smart_art.to_markdown(&options.bullet_markers)
+                }
+            };
+            match &options.block_postprocess {
+                Some(postprocess) => {
+                    markdown.write_all(postprocess.apply(block_content, block).as_bytes())?
+                }
+                None => markdown.write_all(block.as_bytes())?,
+            }
+            if let MarkdownContent::Paragraph(_) = block_content {
+                writeln!(markdown)?;
+            }
+            match content.get(index + 1) {
+                Some(next) if self.is_tight_list(block_content, next) => (),
+                Some(next)
+                    if options.paragraph_spacing == ParagraphSpacing::Tight
+                        && self.is_text_paragraph(block_content)
+                        && self.is_text_paragraph(next) => {}
+                Some(_) => markdown.write_all("\n".repeat(1 + extra_blank_lines).as_bytes())?,
+                None => (),
+            }
+        }
+
+        for note in &self.notes {
+            let paragraphs: Vec<String> = note
+                .paragraphs
+                .iter()
+                .map(|paragraph| {
+                    paragraph.to_markdown(&self.styles, &mut numberings, self, options)
+                })
+                .collect();
+            writeln!(
+                markdown,
+                "\n[^{}]: {}",
+                note.label,
+                paragraphs.join("\n\n    ")
+            )?;
+        }
+
+        if let (true, Some(footer)) = (options.headers_footers, self.footers.last()) {
+            let footer = footer.to_markdown(&self.styles, &mut numberings, self, options);
+            writeln!(markdown, "\n---\n\n{footer}")?;
+        }
+
+        Ok(())
+    }
+
+    /// Convert the document to markdown like [`MarkdownDocument::to_markdown_with`], and check the
+    /// result for unbalanced emphasis and malformed tables, e.g. from
+    /// [`CellParagraphJoin::Newline`]. With [`MarkdownOptions::strict`] raw HTML, e.g. `<br/>`,
+    /// is reported too.
+    pub fn to_markdown_checked(
+        &self,
+        options: &MarkdownOptions,
+    ) -> Result<String, MarkdownLintError> {
+        let markdown = self.to_markdown_with(options);
+        let violations = lint::lint(&markdown, !options.strict);
+        if violations.is_empty() {
+            Ok(markdown)
+        } else {
+            Err(MarkdownLintError { violations })
+        }
+    }
+
+    /// Whether the content is a paragraph that isn't a list item.
+    fn is_text_paragraph(&self, content: &MarkdownContent) -> bool {
+        match content {
+            MarkdownContent::Paragraph(paragraph) => {
+                paragraph.resolved_style(&self.styles).numbering.is_none()
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether two consecutive paragraphs are items of a tight list, i.e. list items of the same
+    /// style with contextual spacing, so no blank line goes between them.
+    fn is_tight_list(&self, content: &MarkdownContent, next: &MarkdownContent) -> bool {
+        let (MarkdownContent::Paragraph(paragraph), MarkdownContent::Paragraph(next)) =
+            (content, next)
+        else {
+            return false;
+        };
+        let style = paragraph.resolved_style(&self.styles);
+        let next_style = next.resolved_style(&self.styles);
+        style.numbering.is_some()
+            && next_style.numbering.is_some()
+            && style.style_id == next_style.style_id
+            && style.contextual_spacing == Some(true)
+            && next_style.contextual_spacing == Some(true)
+    }
+
+    pub fn to_plain_text(&self) -> String {
+        self.to_plain_text_with(&PlainTextOptions::default())
+    }
+
+    /// The text of the document without any markup, e.g. for search indexing.
+    pub fn to_plain_text_with(&self, options: &PlainTextOptions) -> String {
+        let mut paragraphs: Vec<String> = vec![];
+        if let Some(title) = &self.title {
+            paragraphs.push(title.to_string());
+        }
+        let push_table = |paragraphs: &mut Vec<String>, table: &MarkdownTable| {
+            for row in table {
+                let cells: Vec<String> = row
+                    .cells
+                    .iter()
+                    .map(|cell| {
+                        let texts: Vec<String> = cell
+                            .paragraphs
+                            .iter()
+                            .map(|paragraph| paragraph.to_plain_text(options))
+                            .collect();
+                        texts.join(" ")
+                    })
+                    .collect();
+                paragraphs.push(cells.join(&options.cell_separator));
+            }
+        };
+        for content in &self.content {
+            match content {
+                MarkdownContent::Paragraph(paragraph) => {
+                    paragraphs.push(paragraph.to_plain_text(options))
+                }
+                MarkdownContent::Table(table) => push_table(&mut paragraphs, table),
+                MarkdownContent::Chart(chart) => {
+                    if let Some(title) = &chart.title {
+                        paragraphs.push(title.to_string());
+                    }
+                    push_table(&mut paragraphs, &chart.table);
+                }
+                MarkdownContent::SmartArt(smart_art) => {
+                    paragraphs.extend(smart_art.to_plain_text())
+                }
+            }
+        }
+        paragraphs.join(&options.paragraph_separator)
+    }
+
+    /// Each table of the document, including the data of charts, as RFC 4180 CSV. Cells are
+    /// flattened to plain text, with their paragraphs joined by a space, and rows are padded to
+    /// the same number of fields.
+    pub fn tables_as_csv(&self) -> Vec<String> {
+        let options = PlainTextOptions::default();
+        self.content
+            .iter()
+            .filter_map(|content| match content {
+                MarkdownContent::Table(table) => Some(table),
+                MarkdownContent::Chart(chart) => Some(&chart.table),
+                MarkdownContent::Paragraph(_) | MarkdownContent::SmartArt(_) => None,
+            })
+            .map(|table| {
+                let columns = table.iter().map(|row| row.columns()).max().unwrap_or(0);
+                table
+                    .iter()
+                    .map(|row| {
+                        let cells = row.grid_cells();
+                        let fields: Vec<String> = (0..columns)
+                            .map(|c| {
+                                let texts: Vec<String> = cells
+                                    .get(c)
+                                    .into_iter()
+                                    .flat_map(|cell| &cell.paragraphs)
+                                    .map(|paragraph| paragraph.to_plain_text(&options))
+                                    .collect();
+                                csv_field(&texts.join(" "))
+                            })
+                            .collect();
+                        fields.join(",") + "\r\n"
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Convert to markdown without writing any files, and list the images that the markdown refers
+    /// to, so that the caller can write them where they like.
+    pub fn to_markdown_with_manifest(
+        &self,
+        options: &MarkdownOptions,
+    ) -> (String, Vec<ImageManifestEntry>) {
+        let markdown = self.to_markdown_with(&MarkdownOptions {
+            export_images: false,
+            ..options.clone()
+        });
+        let mut manifest: Vec<ImageManifestEntry> = vec![];
+        let images = self
+            .paragraphs()
+            .flat_map(|paragraph| &paragraph.blocks)
+            .filter(|block| block.text_type == TextType::Image);
+        for block in images {
+            let reference = link_target(&block.text);
+            if manifest.iter().any(|entry| entry.reference == reference) {
+                continue;
+            }
+            let path = reference.trim_start_matches("./");
+            let Some(data) = self
+                .images
+                .iter()
+                .find(|(key, _)| key.as_str() == path || key.ends_with(&format!("/{path}")))
+                .map(|(_, data)| data)
+            else {
+                continue;
+            };
+            let file_name = path.rsplit('/').next().unwrap_or(path);
+            manifest.push(ImageManifestEntry {
+                reference: reference.to_string(),
+                file_name: file_name.to_string(),
+                mime_type: image_mime_type(file_name, data),
+                data: data.clone(),
+            });
+        }
+        (markdown, manifest)
+    }
+
+    /// The font families used in the document, including the default font.
+    pub fn fonts_used(&self) -> BTreeSet<String> {
+        self.paragraphs()
+            .flat_map(|paragraph| &paragraph.blocks)
+            .filter_map(|block| block.style.as_ref()?.font.clone())
+            .chain(self.default_font.clone())
+            .collect()
+    }
+
+    /// All paragraphs of the document, including those in tables and notes.
+    fn paragraphs(&self) -> impl Iterator<Item = &MarkdownParagraph> {
+        let content = self
+            .content
+            .iter()
+            .flat_map(|content| -> Vec<&MarkdownParagraph> {
+                match content {
+                    MarkdownContent::Paragraph(paragraph) => vec![paragraph],
+                    MarkdownContent::Table(table) => table
+                        .iter()
+                        .flat_map(|row| row.cells.iter().flat_map(|cell| &cell.paragraphs))
+                        .collect(),
+                    MarkdownContent::Chart(chart) => chart
+                        .table
+                        .iter()
+                        .flat_map(|row| row.cells.iter().flat_map(|cell| &cell.paragraphs))
+                        .collect(),
+                    MarkdownContent::SmartArt(_) => vec![],
+                }
+            });
+        content.chain(self.notes.iter().flat_map(|note| &note.paragraphs))
+    }
+
+    /// All paragraphs of the document, like [`MarkdownDocument::paragraphs`], for changing them.
+    fn paragraphs_mut(&mut self) -> impl Iterator<Item = &mut MarkdownParagraph> {
+        let content = self
+            .content
+            .iter_mut()
+            .flat_map(|content| -> Vec<&mut MarkdownParagraph> {
+                match content {
+                    MarkdownContent::Paragraph(paragraph) => vec![paragraph],
+                    MarkdownContent::Table(table) => table
+                        .iter_mut()
+                        .flat_map(|row| row.cells.iter_mut().flat_map(|cell| &mut cell.paragraphs))
+                        .collect(),
+                    MarkdownContent::Chart(chart) => chart
+                        .table
+                        .iter_mut()
+                        .flat_map(|row| row.cells.iter_mut().flat_map(|cell| &mut cell.paragraphs))
+                        .collect(),
+                    MarkdownContent::SmartArt(_) => vec![],
+                }
+            });
+        content.chain(self.notes.iter_mut().flat_map(|note| &mut note.paragraphs))
+    }
+
+    /// Convert a table to a markdown pipe table.
+    fn table_to_markdown(
+        &self,
+        table: &MarkdownTable,
+        numberings: &mut HashMap<(isize, isize), usize>,
+        options: &MarkdownOptions,
+    ) -> String {
+        let table_with_simple_cells: Vec<(bool, Vec<String>)> = table
+            .iter()
+            .map(|row| {
+                // Markdown can't merge cells: a cell that spans columns is repeated in each, and
+                // one that continues a vertical merge is empty.
+                let row_content: Vec<String> = row
+                    .grid_cells()
+                    .into_iter()
+                    .map(|cell| {
+                        if cell.v_merge == Some(VMergeKind::Continue) {
+                            return options.vertical_merge_marker.clone().unwrap_or_default();
+                        }
+                        let paragraphs: Vec<String> = cell
+                            .paragraphs
+                            .iter()
+                            .map(|paragraph| {
+                                // A pipe in the text would end the cell, but one in e.g. a link
+                                // target is kept.
+                                let mut paragraph = paragraph.clone();
+                                for block in &mut paragraph.blocks {
+                                    if block.text_type == TextType::Text {
+                                        block.text = block.text.replace('|', CELL_PIPE);
+                                    }
+                                }
+                                // A line break would end the row.
+                                paragraph
+                                    .to_markdown(&self.styles, numberings, self, options)
+                                    .replace("  \n", "<br/>")
+                                    .replace(CELL_PIPE, "\\|")
+                            })
+                            .collect();
+                        options.cell_paragraph_join.join(&paragraphs)
+                    })
+                    .collect();
+                (row.is_header, row_content)
+            })
+            .collect();
+        let table_with_simple_cells = collapse_header_rows(table_with_simple_cells);
+        let column_lengths =
+            max_lengths_per_column(&table_with_simple_cells, options.table_min_column_width);
+        let alignments = column_alignments(table);
+        let divider_cells: Vec<String> = column_lengths
+            .iter()
+            .enumerate()
+            .map(|(c, width)| {
+                let width = if options.pad_table_divider { *width } else { 3 };
+                match alignments.get(c).copied().flatten() {
+                    Some(alignment) => alignment.divider(width),
+                    None => "-".repeat(width),
+                }
+            })
+            .collect();
+        let divider = &if options.pad_table_divider {
+            table_row_to_markdown(&column_lengths, &divider_cells)
+        } else {
+            table_row_to_markdown(&vec![0; column_lengths.len()], &divider_cells)
+        };
+        table_with_simple_cells.iter().enumerate().fold(
+            "".to_string(),
+            |mut acc, (i, (is_header, row))| {
+                let markdown_row = &table_row_to_markdown(&column_lengths, row);
+                if i == 0 {
+                    if *is_header {
+                        acc.push_str(markdown_row);
+                        acc.push_str(divider);
+                    } else {
+                        acc.push_str(&table_row_to_markdown(
+                            &column_lengths,
+                            &vec!["".to_string(); column_lengths.len()],
+                        ));
+                        acc.push_str(divider);
+                        acc.push_str(markdown_row);
+                    }
+                } else {
+                    acc.push_str(markdown_row);
+                }
+                if i == table_with_simple_cells.len() {
+                    acc.push('\n');
+                }
+                acc
+            },
+        )
+    }
+}
+
+/// The alignment of each grid column of a table, from the justification of its cells. When the
+/// cells disagree, the alignment of the cell in the first row is used.
+fn column_alignments(table: &MarkdownTable) -> Vec<Option<CellAlignment>> {
+    let rows: Vec<Vec<&MarkdownTableCell>> = table.iter().map(|row| row.grid_cells()).collect();
+    let columns = rows.iter().map(|cells| cells.len()).max().unwrap_or(0);
+    (0..columns)
+        .map(|c| {
+            let mut alignments = rows
+                .iter()
+                .filter_map(|cells| cells.get(c))
+                .filter(|cell| cell.v_merge != Some(VMergeKind::Continue))
+                .map(|cell| cell.alignment);
+            let first = alignments.next().flatten();
+            if alignments.all(|alignment| alignment == first) {
+                first
+            } else {
+                rows.first()
+                    .and_then(|cells| cells.get(c))
+                    .and_then(|cell| cell.alignment)
+            }
+        })
+        .collect()
+}
+
+/// The equations of a table that only lays out numbered equations, with the equation in one cell
+/// and its number, e.g. `(1)`, in another. The number is kept as a `\tag{1}` of the equation.
+fn numbered_equations(table: &MarkdownTable) -> Option<Vec<MarkdownParagraph>> {
+    if table.is_empty() {
+        return None;
+    }
+    table
+        .iter()
+        .map(|row| {
+            let mut equation: Option<&TextBlock> = None;
+            let mut number: Option<String> = None;
+            for cell in &row.cells {
+                let blocks: Vec<&TextBlock> = cell
+                    .paragraphs
+                    .iter()
+                    .flat_map(|paragraph| &paragraph.blocks)
+                    .filter(|block| {
+                        block.text_type != TextType::Text || !block.text.trim().is_empty()
+                    })
+                    .collect();
+                match blocks.as_slice() {
+                    [] => {}
+                    [block] if block.text_type == TextType::MathBlock && equation.is_none() => {
+                        equation = Some(block)
+                    }
+                    _ if number.is_none()
+                        && blocks.iter().all(|block| block.text_type == TextType::Text) =>
+                    {
+                        let text: String = blocks.iter().map(|block| block.text.as_str()).collect();
+                        number = Some(equation_number(&text)?.to_string());
+                    }
+                    _ => return None,
+                }
+            }
+            let mut paragraph = MarkdownParagraph::new();
+            paragraph.blocks.push(TextBlock::new(
+                format!("{} \\tag{{{}}}", equation?.text, number?),
+                None,
+                TextType::MathBlock,
+            ));
+            Some(paragraph)
+        })
+        .collect()
+}
+
+/// The label of a caption, e.g. `Table 1` for `Table 1: Sales by region`.
+fn caption_label(caption: &str) -> &str {
+    let caption = caption.trim();
+    let end = caption
+        .find([':', '.', '\u{2013}', '\u{2014}'])
+        .unwrap_or(caption.len());
+    caption[..end].trim_end_matches([' ', '-']).trim()
+}
+
+/// Indent a list item, which is rendered with four spaces per level, as set by `list_indent`.
+/// Unless it is indented with tabs, it is indented by at least the content offset of its parent
+/// item, e.g. 6 for `(2.a) `, so that CommonMark nests it in the parent. `parents` has the indent
+/// and content offset of the last item at each level.
+fn nest_list_item(
+    markdown: String,
+    level: usize,
+    parents: &mut Vec<(usize, usize)>,
+    list_indent: ListIndent,
+) -> String {
+    let Some(item) = markdown.strip_prefix(&"    ".repeat(level)) else {
+        return markdown;
+    };
+    if list_indent == ListIndent::Tabs {
+        return format!("{}{item}", "\t".repeat(level));
+    }
+    let child_indent = |parents: &[(usize, usize)]| {
+        parents
+            .last()
+            .map_or(0, |&(indent, offset)| match list_indent {
+                ListIndent::Spaces(spaces) => (indent + spaces).max(offset),
+                _ => offset,
+            })
+    };
+    parents.truncate(level);
+    // Skipped levels, e.g. a second level item right after a paragraph.
+    while parents.len() < level {
+        let indent = child_indent(parents);
+        parents.push((indent, indent));
+    }
+    let indent = child_indent(parents);
+    let marker = item.split(' ').next().unwrap_or_default();
+    parents.push((indent, indent + marker.chars().count() + 1));
+    format!("{}{item}", " ".repeat(indent))
+}
+
+/// The formatting of the character styles in `word/styles.xml`, including that of their base
+/// styles, keyed by style id. A paragraph style that is linked to a character style (`w:link`),
+/// e.g. `Heading1` and `Heading1Char`, has the formatting of the character style, which Word
+/// uses when the paragraph style is applied to a run.
+fn character_styles(styles: Option<Node>) -> HashMap<String, BlockStyle> {
+    let styles: HashMap<&str, Node> = w_children(styles, "style")
+        .into_iter()
+        .filter_map(|style| Some((style.attribute((W_NS, "styleId"))?, style)))
+        .collect();
+    let value = |style: Node, name: &str| {
+        w_children(Some(style), name)
+            .first()
+            .and_then(|node| node.attribute((W_NS, "val")))
+            .map(|value| value.to_string())
+    };
+
+    let mut character_styles = HashMap::new();
+    for (id, style) in &styles {
+        let character_style = match style.attribute((W_NS, "type")) {
+            Some("character") => *style,
+            Some("paragraph") => match value(*style, "link")
+                .and_then(|link| styles.get(link.as_str()))
+                .filter(|linked| linked.attribute((W_NS, "type")) == Some("character"))
+            {
+                Some(linked) => *linked,
+                None => continue,
+            },
+            _ => continue,
+        };
+        // The base styles, up to a limit in case they form a cycle.
+        let mut chain = vec![character_style];
+        while let Some(base) = value(chain[chain.len() - 1], "basedOn")
+            .and_then(|base| styles.get(base.as_str()))
+            .filter(|_| chain.len() < 16)
+        {
+            chain.push(*base);
+        }
+        let mut block_style = BlockStyle::new();
+        for style in chain.iter().rev() {
+            block_style.combine_with(&run_properties_style(
+                w_children(Some(*style), "rPr").first().copied(),
+            ));
+        }
+        character_styles.insert(id.to_string(), block_style);
+    }
+    character_styles
+}
+
+/// Set the bold, italics, underline and strike toggles of run properties, either on or
+/// explicitly off, e.g. for `<w:b w:val="0"/>`. Toggles that are not there are left as they are.
+fn apply_toggles(block_style: &mut BlockStyle, character_property: &CharacterProperty) {
+    let bold = character_property
+        .bold
+        .as_ref()
+        .map(|bold| bold.value != Some(false));
+    let italics = match &character_property.emphasis {
+        Some(_) => Some(true),
+        None => character_property
+            .italics
+            .as_ref()
+            .map(|italics| italics.value != Some(false)),
+    };
+    let underline = character_property
+        .underline
+        .as_ref()
+        .map(|underline| !matches!(underline.val, Some(UnderlineStyle::None)));
+    let strike = match (&character_property.strike, &character_property.dstrike) {
+        (None, None) => None,
+        (strike, dstrike) => Some(
+            strike
+                .as_ref()
+                .is_some_and(|strike| strike.value != Some(false))
+                || dstrike
+                    .as_ref()
+                    .is_some_and(|dstrike| dstrike.value != Some(false)),
+        ),
+    };
+    let off = &mut block_style.switched_off;
+    for (on, off, toggle) in [
+        (&mut block_style.bold, &mut off.bold, bold),
+        (&mut block_style.italics, &mut off.italics, italics),
+        (&mut block_style.underline, &mut off.underline, underline),
+        (&mut block_style.strike, &mut off.strike, strike),
+    ] {
+        if let Some(toggle) = toggle {
+            *on = toggle;
+            *off = !toggle;
+        }
+    }
+}
+
+/// The formatting of raw run properties (`w:rPr`), e.g. of a character style.
+fn run_properties_style(properties: Option<Node>) -> BlockStyle {
+    let font = package::run_font(properties);
+    let vertical_align = package::vertical_align(properties);
+    BlockStyle {
+        bold: package::is_on(properties, "b"),
+        italics: package::is_on(properties, "i"),
+        underline: w_children(properties, "u")
+            .first()
+            .is_some_and(|underline| underline.attribute((W_NS, "val")) != Some("none")),
+        strike: package::is_on(properties, "strike") || package::is_on(properties, "dstrike"),
+        size: w_children(properties, "sz")
+            .first()
+            .and_then(|size| size.attribute((W_NS, "val"))?.parse().ok()),
+        code: is_inline_code(font.as_deref(), package::run_shading(properties).as_deref()),
+        rtl: package::is_on(properties, "rtl"),
+        color: package::run_color(properties),
+        highlight: package::run_highlight(properties),
+        superscript: vertical_align.as_deref() == Some("superscript"),
+        subscript: vertical_align.as_deref() == Some("subscript"),
+        font,
+        switched_off: SwitchedOff {
+            bold: package::is_off(properties, "b"),
+            italics: package::is_off(properties, "i"),
+            underline: w_children(properties, "u")
+                .first()
+                .is_some_and(|underline| underline.attribute((W_NS, "val")) == Some("none")),
+            strike: package::is_off(properties, "strike") && !package::is_on(properties, "dstrike"),
+        },
+        ..BlockStyle::new()
+    }
+}
+
+/// The name of a bookmark block, e.g. `_Ref1` for `<a name="_Ref1"></a>`.
+fn bookmark_name(block: &TextBlock) -> Option<&str> {
+    if block.text_type != TextType::BookmarkLink {
+        return None;
+    }
+    block
+        .text
+        .strip_prefix(r#"<a name=""#)?
+        .strip_suffix(r#""></a>"#)
+}
+
+/// Replace the font sizes (half-points) of the block styles in the JSON of a document, see
+/// [`MarkdownOptions::font_size_json`].
+fn font_sizes_to_json(json: &mut serde_json::Value, mode: FontSizeJson) {
+    match json {
+        serde_json::Value::Object(object) => {
+            // Only block styles have a size, next to their formatting flags.
+            if object.contains_key("bold") {
+                let style = BlockStyle {
+                    size: object
+                        .get("size")
+                        .and_then(|size| size.as_i64())
+                        .map(|size| size as isize),
+                    ..BlockStyle::new()
+                };
+                let size = match mode {
+                    FontSizeJson::HalfPoints => None,
+                    FontSizeJson::Points => style.size_in_points().map(serde_json::Value::from),
+                    FontSizeJson::Named => style.size_name().map(serde_json::Value::from),
+                };
+                if let Some(size) = size {
+                    object.insert("size".to_string(), size);
+                }
+            }
+            object
+                .values_mut()
+                .for_each(|value| font_sizes_to_json(value, mode));
+        }
+        serde_json::Value::Array(values) => values
+            .iter_mut()
+            .for_each(|value| font_sizes_to_json(value, mode)),
+        _ => (),
+    }
+}
+
+/// Merge the header rows at the start of a table into one, as markdown has a single header row.
+/// Header rows that repeat the same text, e.g. a header repeated on every page, are kept once.
+fn collapse_header_rows(rows: Vec<(bool, Vec<String>)>) -> Vec<(bool, Vec<String>)> {
+    let header_count = rows.iter().take_while(|(is_header, _)| *is_header).count();
+    if header_count < 2 {
+        return rows;
+    }
+    let mut rows = rows.into_iter();
+    let mut header: Vec<String> = vec![];
+    for (_, row) in rows.by_ref().take(header_count) {
+        for (c, cell) in row.into_iter().enumerate() {
+            match header.get_mut(c) {
+                None => header.push(cell),
+                Some(merged)
+                    if cell.is_empty() || merged.split("<br/>").any(|line| line == cell) => {}
+                Some(merged) if merged.is_empty() => *merged = cell,
+                Some(merged) => *merged = format!("{merged}<br/>{cell}"),
+            }
+        }
+    }
+    std::iter::once((true, header)).chain(rows).collect()
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MarkdownContent {
+    Paragraph(Box<MarkdownParagraph>),
+    Table(MarkdownTable),
+    /// A chart, of which only the data is kept
+    Chart(MarkdownChart),
+    /// A SmartArt diagram, of which only the text is kept
+    SmartArt(MarkdownSmartArt),
+}
+
+pub type MarkdownTable = Vec<MarkdownTableRow>;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkdownTableRow {
+    is_header: bool,
+    cells: Vec<MarkdownTableCell>,
+    /// Indexes of the cells that continue a vertical merge from the row above
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    continued: Vec<usize>,
+    /// The background colors of the shaded cells, e.g. `FF0000`, keyed by cell index
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    backgrounds: BTreeMap<usize, String>,
+}
+
+impl MarkdownTableRow {
+    /// The background color of a cell, e.g. `FF0000`, if it is shaded.
+    pub fn background(&self, cell: usize) -> Option<&str> {
+        self.backgrounds.get(&cell).map(|color| color.as_str())
+    }
+
+    /// The number of grid columns of the row, counting the columns that cells span.
+    pub fn columns(&self) -> usize {
+        self.cells.iter().map(MarkdownTableCell::span).sum()
+    }
+
+    /// The cell of each grid column of the row, so a cell that spans columns is repeated.
+    pub fn grid_cells(&self) -> Vec<&MarkdownTableCell> {
+        self.cells
+            .iter()
+            .flat_map(|cell| std::iter::repeat_n(cell, cell.span()))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkdownTableCell {
+    pub paragraphs: Vec<MarkdownParagraph>,
+    /// The number of grid columns that the cell spans (`w:gridSpan`), when more than one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grid_span: Option<usize>,
+    /// Whether the cell starts or continues a vertical merge (`w:vMerge`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub v_merge: Option<VMergeKind>,
+    /// The justification (`w:jc`) of the first paragraph of the cell
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alignment: Option<CellAlignment>,
+}
+
+impl MarkdownTableCell {
+    pub fn new(paragraphs: Vec<MarkdownParagraph>) -> Self {
+        MarkdownTableCell {
+            paragraphs,
+            grid_span: None,
+            v_merge: None,
+            alignment: None,
+        }
+    }
+
+    /// The number of grid columns that the cell spans, at least one.
+    pub fn span(&self) -> usize {
+        self.grid_span.unwrap_or(1).max(1)
+    }
+}
+
+/// How a table cell takes part in a vertical merge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum VMergeKind {
+    /// The first cell of the merge, which has the content
+    Restart,
+    /// A cell below the first one, whose content is hidden
+    Continue,
+}
+
+/// The horizontal alignment of a table cell, shown by the colons of a pipe table divider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CellAlignment {
+    Left,
+    Center,
+    Right,
+}
+
+impl CellAlignment {
+    fn from_justification(justification: &str) -> Option<Self> {
+        match justification {
+            "left" | "start" => Some(CellAlignment::Left),
+            "center" => Some(CellAlignment::Center),
+            "right" | "end" => Some(CellAlignment::Right),
+            _ => None,
+        }
+    }
+
+    /// The divider cell of a column with this alignment, e.g. `--:`. Left-aligned columns keep
+    /// the plain `---`, which is left-aligned already, like pandoc writes them.
+    fn divider(self, width: usize) -> String {
+        let dashes = "-".repeat(width.max(3) - 2);
+        match self {
+            CellAlignment::Left => "-".repeat(width),
+            CellAlignment::Center => format!(":{dashes}:"),
+            CellAlignment::Right => format!("-{dashes}:"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::fs;
+    use std::sync::Arc;
+
+    // Import necessary items
+    use super::*;
+
+    #[test]
+    fn test_headers() {
+        let markdown_pandoc = fs::read_to_string("./test/headers.md").unwrap();
+        let markdown_doc = MarkdownDocument::from_file("./test/headers.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert_eq!(markdown_pandoc, markdown);
+    }
+
+    #[test]
+    fn test_bullets() {
+        let markdown_pandoc = fs::read_to_string("./test/lists.md").unwrap();
+        let markdown_doc = MarkdownDocument::from_file("./test/lists.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert_eq!(markdown_pandoc, markdown);
+    }
+
+    #[test]
+    fn test_images() {
+        let markdown_pandoc = fs::read_to_string("./test/image.md").unwrap();
+        let markdown_doc = MarkdownDocument::from_file("./test/image.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert_eq!(markdown_pandoc, markdown);
+    }
+
+    #[test]
+    fn test_links() {
+        let markdown_pandoc = fs::read_to_string("./test/links.md").unwrap();
+        let markdown_doc = MarkdownDocument::from_file("./test/links.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert_eq!(markdown_pandoc, markdown);
+    }
+
+    #[test]
+    fn test_tables() {
+        let markdown_pandoc = fs::read_to_string("./test/tables.md").unwrap();
+        let markdown_doc = MarkdownDocument::from_file("./test/tables.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert_eq!(markdown_pandoc, markdown);
+    }
+
+    #[test]
+    fn test_one_row_table() {
+        let markdown_pandoc = fs::read_to_string("./test/table_one_row.md").unwrap();
+        let markdown_doc = MarkdownDocument::from_file("./test/table_one_row.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert_eq!(markdown_pandoc, markdown);
+    }
+
+    #[test]
+    fn test_table_with_list_cell() {
+        let markdown_pandoc = fs::read_to_string("./test/table_with_list_cell.md").unwrap();
+        let markdown_doc = MarkdownDocument::from_file("./test/table_with_list_cell.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert_eq!(markdown_pandoc, markdown);
+    }
+
+    #[test]
+    fn test_tables_separated_with_rawblock() {
+        let markdown_pandoc =
+            fs::read_to_string("./test/tables_separated_with_rawblock.md").unwrap();
+        let markdown_doc =
+            MarkdownDocument::from_file("./test/tables_separated_with_rawblock.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert_eq!(markdown_pandoc, markdown);
+    }
+
+    #[test]
+    fn test_ordinal_text_list() {
+        let markdown_doc = MarkdownDocument::from_file("./test/lists_ordinal_text.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.contains("First. Gather the ingredients"));
+        assert!(markdown.contains("Second. Mix them"));
+        assert!(markdown.contains("Third. Bake"));
+    }
+
+    #[test]
+    fn test_math_placeholder() {
+        let markdown_doc = MarkdownDocument::from_file("./test/math.docx").unwrap();
+        let options = MarkdownOptions {
+            math_output: MathOutput::Placeholder("[equation]".to_string()),
+            ..Default::default()
+        };
+        let markdown = markdown_doc.to_markdown_with(&options);
+        assert!(markdown.contains("A fraction [equation] and a power [equation] inline."));
+        assert!(markdown.contains("[equation]\n"));
+        assert!(!markdown.contains('$'));
+    }
+
+    #[test]
+    fn test_smart_tags() {
+        let markdown_doc = MarkdownDocument::from_file("./test/smart_tags.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.contains("We met in Amsterdam on Monday."));
+        let json = markdown_doc.to_json(false).unwrap();
+        assert!(json.contains(r#""smartTags":[{"element":"place""#));
+    }
+
+    #[test]
+    fn test_compact_table_divider() {
+        let markdown_doc = MarkdownDocument::from_file("./test/tables.docx").unwrap();
+        let options = MarkdownOptions {
+            pad_table_divider: false,
+            ..Default::default()
+        };
+        let markdown = markdown_doc.to_markdown_with(&options);
+        assert!(markdown.contains("| Name           | Game       | Fame      | Blame             |\n| --- | --- | --- | --- |\n"));
+        let dividers: Vec<&str> = markdown
+            .lines()
+            .filter(|line| line.starts_with("| -"))
+            .collect();
+        assert!(!dividers.is_empty());
+        assert!(dividers
+            .iter()
+            .all(|divider| divider.replace("| --- ", "") == "|"));
+    }
+
+    #[test]
+    fn test_chart() {
+        let markdown_doc = MarkdownDocument::from_file("./test/chart.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.contains("*Chart: Fruit sales*"));
+        assert!(markdown.contains("|         | 2023 | 2024 |"));
+        assert!(markdown.contains("| Apples  | 10   | 12   |"));
+        assert!(markdown.contains("| Bananas | 7    | 9    |"));
+        let json = markdown_doc.to_json(false).unwrap();
+        assert!(json.contains(r#""title":"Fruit sales","chartType":"barChart""#));
+    }
+
+    #[test]
+    fn test_track_rsids() {
+        let options = MarkdownOptions {
+            track_rsids: true,
+            ..Default::default()
+        };
+        let markdown_doc = MarkdownDocument::from_file_with("./test/rsids.docx", &options).unwrap();
+        let json = markdown_doc.to_json(false).unwrap();
+        assert!(json.contains(r#""rsidR":"00A1B2C3""#));
+        assert!(json.contains(
+            r#""text":"First edit second edit","rsidR":"00D4E5F6","rsidRPr":"00112233""#
+        ));
+        // The revision ids don't split the runs, or their emphasis.
+        assert_eq!(
+            markdown_doc.to_markdown(false),
+            "**First edit second edit**\n"
+        );
+
+        let markdown_doc = MarkdownDocument::from_file("./test/rsids.docx").unwrap();
+        let json = markdown_doc.to_json(false).unwrap();
+        assert!(!json.contains("rsid"));
+    }
+
+    #[test]
+    fn test_emphasis_markers() {
+        let block = TextBlock::new(
+            "text".to_string(),
+            Some(BlockStyle {
+                bold: true,
+                italics: true,
+                ..BlockStyle::new()
+            }),
+            TextType::Text,
+        );
+        let paragraph_style = ParagraphStyle::new();
+        let render = |bold_marker, italic_marker| {
+            let options = MarkdownOptions {
+                bold_marker,
+                italic_marker,
+                ..Default::default()
+            };
+            block.to_markdown(&paragraph_style, &options)
+        };
+        use EmphasisMarker::{Asterisk, Underscore};
+        assert_eq!(render(Asterisk, Asterisk), "***text***");
+        assert_eq!(render(Asterisk, Underscore), "_**text**_");
+        assert_eq!(render(Underscore, Asterisk), "*__text__*");
+        assert_eq!(render(Underscore, Underscore), "___text___");
+
+        let italic = TextBlock::new(
+            "text".to_string(),
+            Some(BlockStyle {
+                italics: true,
+                ..BlockStyle::new()
+            }),
+            TextType::Text,
+        );
+        let options = MarkdownOptions {
+            italic_marker: Underscore,
+            ..Default::default()
+        };
+        assert_eq!(italic.to_markdown(&paragraph_style, &options), "_text_");
+
+        // With `__` for bold, underline can't use it as well.
+        let underline = TextBlock::new(
+            "text".to_string(),
+            Some(BlockStyle {
+                underline: true,
+                ..BlockStyle::new()
+            }),
+            TextType::Text,
+        );
+        assert_eq!(
+            underline.to_markdown(&paragraph_style, &MarkdownOptions::default()),
+            "__text__"
+        );
+        let options = MarkdownOptions {
+            bold_marker: Underscore,
+            ..Default::default()
+        };
+        assert_eq!(
+            underline.to_markdown(&paragraph_style, &options),
+            "<u>text</u>"
+        );
+    }
+
+    #[test]
+    fn test_permission_ranges() {
+        let markdown_doc = MarkdownDocument::from_file("./test/permissions.docx").unwrap();
+        let json = markdown_doc.to_json(false).unwrap();
+        assert!(json.contains(
+            r#""permissions":[{"id":"1","editorGroup":"everyone","text":"You may edit this.\nAnd this."}]"#
+        ));
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.contains("You may edit this."));
+    }
+
+    #[test]
+    fn test_file_links() {
+        let path = "./test/file_links.docx";
+        let markdown = MarkdownDocument::from_file(path)
+            .unwrap()
+            .to_markdown(false);
+        assert!(markdown.contains("[report](file:///C:/Users/jane/report.docx)"));
+        assert!(markdown.contains("[website](https://example.com)"));
+
+        let options = MarkdownOptions {
+            on_file_link: FileLinkHandling::Strip,
+            ..Default::default()
+        };
+        let markdown = MarkdownDocument::from_file_with(path, &options)
+            .unwrap()
+            .to_markdown(false);
+        assert!(
+            markdown.contains("See the report, the other and the [website](https://example.com).")
+        );
+
+        let options = MarkdownOptions {
+            on_file_link: FileLinkHandling::Rewrite(Arc::new(|target: &str| {
+                let name = target.rsplit(['/', '\\']).next().unwrap_or(target);
+                format!("./files/{name}")
+            })),
+            ..Default::default()
+        };
+        let markdown = MarkdownDocument::from_file_with(path, &options)
+            .unwrap()
+            .to_markdown(false);
+        assert!(markdown.contains("[report](./files/report.docx)"));
+        assert!(markdown.contains("[other](./files/other.docx)"));
+        assert!(markdown.contains("[website](https://example.com)"));
+    }
+
+    #[test]
+    fn test_normalize_heading_levels() {
+        let mut markdown_doc =
+            MarkdownDocument::from_file("./test/headers_from_level_2.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.starts_with("## Introduction\n"));
+        markdown_doc.normalize_heading_levels();
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.starts_with("# Introduction\n"));
+        assert!(markdown.contains("\n## Background\n"));
+        assert!(markdown.contains("\n# Conclusion\n"));
+
+        // Headings of heading styles without an outline level and of style overrides count too.
+        let paragraph = |style_id: &str, text: &str| {
+            let mut paragraph = MarkdownParagraph::new();
+            paragraph.style = Some(ParagraphStyle {
+                style_id: Some(style_id.to_string()),
+                ..ParagraphStyle::new()
+            });
+            paragraph.blocks = vec![TextBlock::new(text.to_string(), None, TextType::Text)];
+            MarkdownContent::Paragraph(Box::new(paragraph))
+        };
+        let mut markdown_doc = MarkdownDocument::new();
+        for style_id in ["Heading3", "Heading4", "Subtitle"] {
+            markdown_doc
+                .styles
+                .insert(style_id.to_string(), ParagraphStyle::new());
+        }
+        markdown_doc.content = vec![
+            paragraph("Subtitle", "Overview"),
+            paragraph("Heading3", "Scope"),
+            paragraph("Heading4", "Details"),
+        ];
+        markdown_doc.normalize_heading_levels();
+        assert_eq!(
+            markdown_doc.to_markdown(false),
+            "Overview\n\n# Scope\n\n## Details\n"
+        );
+        let options = MarkdownOptions {
+            style_overrides: serde_json::from_str(r#"{ "Subtitle": { "headingLevel": 2 } }"#)
+                .unwrap(),
+            ..Default::default()
+        };
+        markdown_doc.normalize_heading_levels_with(&options);
+        assert_eq!(
+            markdown_doc.to_markdown_with(&options),
+            "# Overview\n\n## Scope\n\n### Details\n"
+        );
+    }
+
+    #[test]
+    fn test_image_crop() {
+        let markdown_doc = MarkdownDocument::from_file("./test/image_cropped.docx").unwrap();
+        let json = markdown_doc.to_json(false).unwrap();
+        assert!(json.contains(
+            r#""image":{"width":200,"height":100,"aspectRatio":2.0,"crop":{"left":10.0,"top":5.0,"right":20.0,"bottom":0.0}}"#
+        ));
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.contains("![A cropped logo](./media/image1.png)"));
+    }
+
+    #[test]
+    fn test_bullet_markers_per_level() {
+        let markdown_doc = MarkdownDocument::from_file("./test/nested_bullets.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.contains("- Fruit\n"));
+        assert!(markdown.contains("    - Apple\n"));
+
+        let options = MarkdownOptions {
+            bullet_markers: vec!["-".to_string(), "*".to_string(), "+".to_string()],
+            ..Default::default()
+        };
+        let markdown = markdown_doc.to_markdown_with(&options);
+        assert!(markdown.contains("- Fruit\n"));
+        assert!(markdown.contains("    * Apple\n"));
+        assert!(markdown.contains("        + Granny Smith\n"));
+    }
+
+    #[test]
+    fn test_alt_chunks() {
+        let markdown_doc = MarkdownDocument::from_file("./test/alt_chunks.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.contains(
+            "Before the chunk\n\nImported heading\n\nSome imported text & more.\n\nAfter the chunk"
+        ));
+        assert!(!markdown.contains("color: red"));
+        // The chunk relationships, which docx-rust leaves out, are found in the package.
+        assert_eq!(markdown_doc.warnings.len(), 1);
+        assert!(markdown_doc.warnings[0].contains("application/rtf"));
+        assert!(!markdown_doc.warnings[0].contains("relationship not found"));
+    }
+
+    #[test]
+    fn test_rel_ids() {
+        let markdown_doc = MarkdownDocument::from_file("./test/image.docx").unwrap();
+        let json = markdown_doc.to_json(false).unwrap();
+        assert!(json.contains(r#""textType":"Image""#));
+        assert!(json.contains(r#""relId":"rId20""#));
+
+        let markdown_doc = MarkdownDocument::from_file("./test/links.docx").unwrap();
+        let json = markdown_doc.to_json(false).unwrap();
+        assert!(json.contains(r#""relId":"rId21""#));
+    }
+
+    #[test]
+    fn test_plain_text_separators() {
+        let markdown_doc = MarkdownDocument::from_file("./test/tables.docx").unwrap();
+        let text = markdown_doc.to_plain_text();
+        assert!(
+            text.starts_with("A table, with and without a header row\nName\tGame\tFame\tBlame\n")
+        );
+        assert!(text.contains("Simple Multiparagraph\tTable Full\n"));
+
+        let options = PlainTextOptions {
+            paragraph_separator: "\n\n".to_string(),
+            cell_separator: " | ".to_string(),
+            ..Default::default()
+        };
+        let text = markdown_doc.to_plain_text_with(&options);
+        assert!(text.contains("Name | Game | Fame | Blame\n\nLebron James | Basketball"));
+    }
+
+    #[test]
+    fn test_plain_text_image_alt_text() {
+        let markdown_doc = MarkdownDocument::from_file("./test/image_cropped.docx").unwrap();
+        assert_eq!(markdown_doc.to_plain_text(), "A cropped logo");
+        let options = PlainTextOptions {
+            include_image_alt_text: false,
+            ..Default::default()
+        };
+        assert_eq!(markdown_doc.to_plain_text_with(&options), "");
+    }
+
+    #[test]
+    fn test_keep_empty_paragraphs() {
+        let markdown_doc = MarkdownDocument::from_file("./test/empty_paragraphs.docx").unwrap();
+        assert_eq!(markdown_doc.content.len(), 2);
+        let json = markdown_doc.to_json(false).unwrap();
+        assert!(!json.contains(r#""size":72"#));
+
+        let options = MarkdownOptions {
+            keep_empty_paragraphs: true,
+            ..Default::default()
+        };
+        let markdown_doc =
+            MarkdownDocument::from_file_with("./test/empty_paragraphs.docx", &options).unwrap();
+        assert_eq!(markdown_doc.content.len(), 3);
+        let json = markdown_doc.to_json(false).unwrap();
+        assert!(json.contains(r#"{"style":{"style":{"bold":false,"italics":false,"underline":false,"strike":false,"size":72}},"blocks":[]}"#));
+    }
+
+    #[test]
+    fn test_pandoc_json() {
+        let markdown_doc = MarkdownDocument::from_file("./test/lists.docx").unwrap();
+        let ast: serde_json::Value = serde_json::from_str(&markdown_doc.to_pandoc_json()).unwrap();
+        assert_eq!(ast["pandoc-api-version"], serde_json::json!([1, 23, 1]));
+        assert!(ast["meta"].is_object());
+        let blocks = ast["blocks"].as_array().unwrap();
+        assert_eq!(blocks[0]["t"], "Header");
+        assert_eq!(blocks[0]["c"][0], 2);
+        assert_eq!(blocks[1]["t"], "OrderedList");
+        let items = blocks[1]["c"][1].as_array().unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(
+            items[0][0]["c"][0],
+            serde_json::json!({"t": "Str", "c": "one"})
+        );
+        assert_eq!(items[1][1]["t"], "OrderedList");
+        assert!(blocks.iter().any(|block| block["t"] == "BulletList"));
+
+        // Ordered lists keep the number format, start and delimiter of their level.
+        let markdown_doc = MarkdownDocument::from_file("./test/long_lists.docx").unwrap();
+        let ast: serde_json::Value = serde_json::from_str(&markdown_doc.to_pandoc_json()).unwrap();
+        assert_eq!(
+            ast["blocks"][0]["c"][0],
+            serde_json::json!([1, {"t": "UpperAlpha"}, {"t": "Period"}])
+        );
+        let markdown_doc = MarkdownDocument::from_file("./test/lists_restarting.docx").unwrap();
+        let ast: serde_json::Value = serde_json::from_str(&markdown_doc.to_pandoc_json()).unwrap();
+        assert_eq!(
+            ast["blocks"][0]["c"][0],
+            serde_json::json!([2, {"t": "Decimal"}, {"t": "Period"}])
+        );
+        let markdown_doc = MarkdownDocument::from_file("./test/lists_parenthesized.docx").unwrap();
+        let ast: serde_json::Value = serde_json::from_str(&markdown_doc.to_pandoc_json()).unwrap();
+        assert_eq!(
+            ast["blocks"][0]["c"][0][2],
+            serde_json::json!({"t": "TwoParens"})
+        );
+
+        let markdown_doc = MarkdownDocument::from_file("./test/tables.docx").unwrap();
+        let ast: serde_json::Value = serde_json::from_str(&markdown_doc.to_pandoc_json()).unwrap();
+        let table = &ast["blocks"][1];
+        assert_eq!(table["t"], "Table");
+        assert_eq!(table["c"][2].as_array().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_preserve_bullet_glyphs() {
+        let markdown_doc = MarkdownDocument::from_file("./test/bullet_glyphs.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.contains("- Go north\n"));
+        assert!(markdown.contains("- Symbol bullet\n"));
+
+        let options = MarkdownOptions {
+            preserve_bullet_glyphs: true,
+            ..Default::default()
+        };
+        let markdown = markdown_doc.to_markdown_with(&options);
+        assert!(markdown.contains("→ Go north\n"));
+        assert!(markdown.contains("• Symbol bullet\n"));
+    }
+
+    #[test]
+    fn test_vertical_merge_marker() {
+        let markdown_doc = MarkdownDocument::from_file("./test/vertical_merge.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.contains("| Fruit     | Apple  |\n|           | Banana |\n"));
+
+        let options = MarkdownOptions {
+            vertical_merge_marker: Some("^".to_string()),
+            ..Default::default()
+        };
+        let markdown = markdown_doc.to_markdown_with(&options);
+        assert!(markdown.contains("| Fruit     | Apple  |\n| ^         | Banana |\n"));
+        let json = markdown_doc.to_json(false).unwrap();
+        assert!(json.contains(r#""continued":[0]"#));
+    }
+
+    #[test]
+    fn test_authors() {
+        let markdown_doc = MarkdownDocument::from_file("./test/authors.docx").unwrap();
+        assert_eq!(
+            markdown_doc.authors,
+            vec!["Ada Lovelace", "Charles Babbage"]
+        );
+        assert_eq!(markdown_doc.contributors, vec!["Mary Somerville"]);
+        let json = markdown_doc.to_json(false).unwrap();
+        assert!(json.contains(r#""authors":["Ada Lovelace","Charles Babbage"]"#));
+        assert!(json.contains(r#""contributors":["Mary Somerville"]"#));
+    }
+
+    #[test]
+    fn test_wrap_width() {
+        let markdown_doc = MarkdownDocument::from_file("./test/long_paragraph.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert_eq!(markdown.lines().count(), 3);
+
+        let options = MarkdownOptions {
+            wrap_width: Some(40),
+            ..Default::default()
+        };
+        let markdown = markdown_doc.to_markdown_with(&options);
+        assert!(markdown.lines().count() > 3);
+        for line in markdown.lines().filter(|line| !line.starts_with('#')) {
+            assert!(line.chars().count() <= 40, "{line}");
+        }
+        assert!(markdown.starts_with("# A heading that is far longer than forty columns\n"));
+        assert!(markdown.contains("[the project page](https://example.com)"));
+
+        // A heading style without an outline level is a heading too, which isn't wrapped.
+        let mut markdown_doc = MarkdownDocument::new();
+        markdown_doc
+            .styles
+            .insert("Heading2".to_string(), ParagraphStyle::new());
+        let mut paragraph = MarkdownParagraph::new();
+        paragraph.style = Some(ParagraphStyle {
+            style_id: Some("Heading2".to_string()),
+            ..ParagraphStyle::new()
+        });
+        paragraph.blocks = vec![TextBlock::new(
+            "A styled heading that is far longer than forty columns".to_string(),
+            None,
+            TextType::Text,
+        )];
+        markdown_doc
+            .content
+            .push(MarkdownContent::Paragraph(Box::new(paragraph)));
+        assert_eq!(
+            markdown_doc.to_markdown_with(&options),
+            "## A styled heading that is far longer than forty columns\n"
+        );
+    }
+
+    #[test]
+    fn test_track_changes_in_tables() {
+        let path = "./test/table_revisions.docx";
+        let parse = |track_changes| {
+            let options = MarkdownOptions {
+                track_changes,
+                ..Default::default()
+            };
+            MarkdownDocument::from_file_with(path, &options)
+                .unwrap()
+                .to_markdown(false)
+        };
+        let accepted = parse(TrackChanges::AcceptAll);
+        assert!(accepted.contains("| Total price |"));
+        assert!(accepted.contains("The price is final."));
+        let rejected = parse(TrackChanges::RejectAll);
+        assert!(rejected.contains("| Total cost |"));
+        assert!(rejected.contains("The price is final for now."));
+        let markup = parse(TrackChanges::ShowMarkup);
+        assert!(markup.contains("| Total {--cost--}{++price++} |"));
+        assert!(markup.contains("The price is final{-- for now--}."));
+    }
+
+    #[test]
+    fn test_tabs_in_list_items() {
+        let markdown_doc = MarkdownDocument::from_file("./test/list_tabs.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.contains("1. Definitions\n"));
+        assert!(markdown.contains("2. Term    of agreement\n"));
+        assert!(!markdown.contains('\t'));
+    }
+
+    #[test]
+    fn test_html_img() {
+        let markdown_doc = MarkdownDocument::from_file("./test/image_cropped.docx").unwrap();
+        let options = MarkdownOptions {
+            image_syntax: ImageSyntax::HtmlImg,
+            ..Default::default()
+        };
+        let markdown = markdown_doc.to_markdown_with(&options);
+        assert!(markdown.contains(
+            r#"<img src="./media/image1.png" alt="A cropped logo" width="200" height="100">"#
+        ));
+    }
+
+    #[test]
+    fn test_no_styles_part() {
+        let markdown_doc = MarkdownDocument::from_file("./test/no_styles.docx").unwrap();
+        assert!(markdown_doc.styles.is_empty());
+        assert_eq!(markdown_doc.warnings.len(), 1);
+        let markdown = markdown_doc.to_markdown(false);
+        assert_eq!(markdown, "Plain text and **bold text**\n");
+    }
+
+    #[test]
+    fn test_link_kinds() {
+        let markdown_doc = MarkdownDocument::from_file("./test/link_kinds.docx").unwrap();
+        let json = markdown_doc.to_json(false).unwrap();
+        assert!(json.contains(r##""text":"[the summary](#summary)","linkKind":"Internal"}"##));
+        assert!(json.contains(r#""relId":"rId70","linkKind":"External","targetMode":"External""#));
+        assert!(json.contains(r#""relId":"rId71","linkKind":"Email","targetMode":"External""#));
+        assert!(json.contains(r#""relId":"rId72","linkKind":"File","targetMode":"External""#));
+    }
+
+    #[test]
+    fn test_under_heading() {
+        let markdown_doc = MarkdownDocument::from_file("./test/headers.docx").unwrap();
+        let section = markdown_doc.under_heading("Fifth level").unwrap();
+        let markdown = section.to_markdown(false);
+        assert!(markdown
+            .starts_with("##### Fifth level\n\nSome more plain text.\n\n###### Sixth level\n"));
+        assert!(markdown.contains("Seventh level"));
+        assert!(!markdown.contains("Fourth level"));
+        assert!(markdown_doc.under_heading("Missing heading").is_none());
+
+        let slice = markdown_doc.slice(1..3);
+        assert_eq!(
+            slice.to_markdown(false),
+            "## Second Level\n\nSome plain text.\n"
+        );
+
+        // Heading styles without an outline level are headings too.
+        let markdown_doc = MarkdownDocument::from_file("./test/heading_styles.docx").unwrap();
+        let section = markdown_doc.under_heading("Introduction").unwrap();
+        assert!(section
+            .to_markdown(false)
+            .starts_with("# Introduction\n\nSome text.\n\n## Background\n"));
+    }
+
+    #[test]
+    fn test_run_style_map() {
+        let markdown_doc = MarkdownDocument::from_file("./test/character_styles.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert_eq!(markdown, "Press Ctrl+C to copy.\n");
+
+        let mut options = MarkdownOptions::default();
+        options.run_style_map.insert(
+            "Keyboard".to_string(),
+            ("<kbd>".to_string(), "</kbd>".to_string()),
+        );
+        let markdown = markdown_doc.to_markdown_with(&options);
+        assert_eq!(markdown, "Press <kbd>Ctrl+C</kbd> to copy.\n");
+    }
+
+    #[test]
+    fn test_paragraph_run_properties() {
+        let markdown_doc =
+            MarkdownDocument::from_file("./test/paragraph_run_properties.docx").unwrap();
+        let resolved = |index: usize| -> Vec<(String, BlockStyle)> {
+            let MarkdownContent::Paragraph(paragraph) = &markdown_doc.content[index] else {
+                panic!("expected a paragraph");
+            };
+            let style = paragraph.resolved_style(&markdown_doc.styles);
+            paragraph
+                .blocks
+                .iter()
+                .map(|block| (block.text.clone(), block.resolved_style(&style)))
+                .collect()
+        };
+        let blocks = resolved(0);
+        assert_eq!(blocks.len(), 3);
+        assert!(blocks.iter().all(|(_, style)| style.bold));
+        assert!(blocks[1].1.italics);
+        assert_eq!(blocks[2].1.size, Some(28));
+
+        let blocks = resolved(1);
+        assert_eq!(blocks[1].0, "this");
+        assert!(blocks[1].1.bold);
+        assert!(!blocks[0].1.bold);
+        assert_eq!(blocks[1].1.size, Some(28));
+
+        // A run that switches bold off explicitly overrides the paragraph.
+        let blocks = resolved(2);
+        assert!(blocks[0].1.bold);
+        assert_eq!(blocks[1].0, ", not bold");
+        assert!(!blocks[1].1.bold);
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.ends_with("**Bold**, not bold\n"));
+    }
+
+    #[test]
+    fn test_notes() {
+        let markdown_pandoc = fs::read_to_string("./test/notes.md").unwrap();
+        let markdown_doc = MarkdownDocument::from_file("./test/notes.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
         assert_eq!(markdown_pandoc, markdown);
+
+        // The separator notes (ids -1 and 0) aren't referenced and are never collected.
+        let ids: Vec<&str> = markdown_doc
+            .notes
+            .iter()
+            .map(|note| note.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["20", "21"]);
+    }
+
+    #[test]
+    fn test_numbering_formats_per_level() {
+        let markdown_doc = MarkdownDocument::from_file("./test/lists_legal.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert_eq!(
+            markdown,
+            "1. Scope\n\n    a. Products\n\n        i. Hardware\n\n        ii. Software\n\n    b. Services\n\n2. Terms\n\n    a. Duration\n"
+        );
+    }
+
+    #[test]
+    fn test_byte_order_mark() {
+        let markdown_doc = MarkdownDocument::from_file("./test/byte_order_mark.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert_eq!(
+            markdown,
+            "# Exported elsewhere\n\nThe document part starts with a BOM.\n"
+        );
+    }
+
+    #[test]
+    fn test_ole_object_preview() {
+        let markdown_doc = MarkdownDocument::from_file("./test/ole_object.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.contains("![Embedded object: Excel.Sheet.12](./media/image2.png)"));
+    }
+
+    #[test]
+    fn test_heading_min_chars() {
+        let markdown_doc = MarkdownDocument::from_file("./test/bold_headings.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.starts_with("**Quarterly results**\n"));
+
+        let options = MarkdownOptions {
+            detect_headings: true,
+            ..Default::default()
+        };
+        let markdown = markdown_doc.to_markdown_with(&options);
+        assert!(markdown.starts_with("# **Quarterly results**\n"));
+        assert!(markdown.contains("\n**Note**\n"));
+        assert!(markdown.contains("\n### **Revenue by region**\n"));
+        assert!(markdown.contains("\n## Contact\n"));
+
+        // Detected headings aren't wrapped either.
+        let markdown = markdown_doc.to_markdown_with(&MarkdownOptions {
+            wrap_width: Some(10),
+            ..options
+        });
+        assert!(markdown.starts_with("# **Quarterly results**\n"));
+        assert!(markdown.contains("\n### **Revenue by region**\n"));
+    }
+
+    #[test]
+    fn test_image_manifest() {
+        let markdown_doc = MarkdownDocument::from_file("./test/ole_object.docx").unwrap();
+        let (markdown, manifest) =
+            markdown_doc.to_markdown_with_manifest(&MarkdownOptions::default());
+        assert!(markdown.contains("](./media/image2.png)"));
+        assert_eq!(manifest.len(), 1);
+        assert_eq!(manifest[0].reference, "./media/image2.png");
+        assert_eq!(manifest[0].file_name, "image2.png");
+        assert_eq!(manifest[0].mime_type, Some("image/png"));
+        assert!(manifest[0].data.starts_with(b"\x89PNG"));
+
+        let markdown_doc = MarkdownDocument::from_file("./test/image.docx").unwrap();
+        let (markdown, manifest) =
+            markdown_doc.to_markdown_with_manifest(&MarkdownOptions::default());
+        let references: Vec<&str> = markdown
+            .split("](")
+            .skip(1)
+            .filter_map(|rest| rest.split(')').next())
+            .filter(|target| target.starts_with("./media/"))
+            .collect();
+        assert!(!references.is_empty());
+        for reference in references {
+            assert!(manifest.iter().any(|entry| entry.reference == reference));
+        }
+    }
+
+    #[test]
+    fn test_repeated_header_rows() {
+        let markdown_doc = MarkdownDocument::from_file("./test/repeated_header.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert_eq!(
+            markdown
+                .lines()
+                .filter(|line| line.starts_with("| --"))
+                .count(),
+            2
+        );
+        assert_eq!(markdown.matches("| Name ").count(), 1);
+        assert!(markdown.contains(
+            "| Name   | Amount |\n| ------ | ------ |\n| Apples | 3      |\n| Pears  | 5      |\n"
+        ));
+        assert!(markdown.contains("| Region | Sales |\n| ------ | ----- |\n| North  | 10    |\n"));
+    }
+
+    #[test]
+    fn test_level_text() {
+        let markdown_doc = MarkdownDocument::from_file("./test/lists_parenthesized.docx").unwrap();
+        let markdown = markdown_doc.to_markdown_with(&MarkdownOptions {
+            use_level_text: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            markdown,
+            "(1) Definitions\n\n(2) Scope\n\n    (2.a) Term\n\n    (2.b) Other term\n\n(3) Obligations\n"
+        );
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.starts_with("1. Definitions\n\n2. Scope\n\n    a. Term\n\n"));
+        let level = &markdown_doc.numberings[&1].levels[1];
+        assert_eq!(level.level_text.as_deref(), Some("(%1.%2)"));
+    }
+
+    #[test]
+    fn test_from_read() {
+        /// A reader that only implements `Read`, like a network stream.
+        struct Stream(File);
+
+        impl Read for Stream {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                self.0.read(buf)
+            }
+        }
+
+        let stream = Stream(File::open("./test/headers.docx").unwrap());
+        let markdown_doc = MarkdownDocument::from_read(stream).unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.starts_with("# A Test of Headers\n\n## Second Level\n"));
+    }
+
+    #[test]
+    fn test_numbered_equation() {
+        let markdown_doc = MarkdownDocument::from_file("./test/numbered_equation.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.contains("The energy is given by\n\n$$E=mc^2 \\tag{1}$$\n\n"));
+        assert!(markdown.contains("| x   | (2) | y   |"));
+        let markdown = markdown_doc.to_markdown_with(&MarkdownOptions {
+            math_output: MathOutput::Placeholder("[equation]".to_string()),
+            ..Default::default()
+        });
+        assert!(markdown.contains("\n\n[equation] (1)\n\n"));
+    }
+
+    #[test]
+    fn test_strict() {
+        let path = "./test/ole_object_no_preview.docx";
+        let markdown_doc = MarkdownDocument::from_file(path).unwrap();
+        assert_eq!(
+            markdown_doc.to_markdown(false),
+            "An attachment without a preview:\n\nThe end.\n"
+        );
+        assert_eq!(
+            markdown_doc.warnings,
+            vec!["Dropped an embedded object without a preview image".to_string()]
+        );
+        let options = MarkdownOptions {
+            strict: true,
+            ..Default::default()
+        };
+        assert!(matches!(
+            MarkdownDocument::from_file_with(path, &options),
+            Err(DocxParseError::Unsupported(_))
+        ));
+        assert!(MarkdownDocument::from_file_with("./test/ole_object.docx", &options).is_ok());
+
+        // A table of contents is generated from the headings, so leaving it out loses nothing.
+        let path = "./test/toc_content_control.docx";
+        assert!(MarkdownDocument::from_file_with(path, &options).is_ok());
+        let path = "./test/block_content_control.docx";
+        assert!(MarkdownDocument::from_file_with(path, &options).is_err());
+    }
+
+    #[test]
+    fn test_cell_paragraph_join() {
+        let markdown_doc = MarkdownDocument::from_file("./test/tables.docx").unwrap();
+        let markdown = markdown_doc.to_markdown_with(&MarkdownOptions {
+            cell_paragraph_join: CellParagraphJoin::Space,
+            ..Default::default()
+        });
+        assert!(markdown.contains("| Simple Multiparagraph | Table Full    |\n"));
+        assert!(markdown.contains("| Of Paragraphs         | In each Cell. |\n"));
+
+        let markdown = markdown_doc.to_markdown_with(&MarkdownOptions {
+            cell_paragraph_join: CellParagraphJoin::Paragraphs,
+            ..Default::default()
+        });
+        assert!(markdown.contains("| <p>Simple</p><p>Multiparagraph</p> |"));
+        assert!(markdown.contains("| <p>In each</p><p>Cell.</p> |"));
+        assert!(markdown.contains("| Lebron James "));
+    }
+
+    #[test]
+    fn test_fonts_used() {
+        let markdown_doc = MarkdownDocument::from_file("./test/fonts.docx").unwrap();
+        assert_eq!(markdown_doc.default_font.as_deref(), Some("Arial"));
+        assert_eq!(
+            markdown_doc.fonts_used(),
+            BTreeSet::from(["Arial".to_string(), "Courier New".to_string()])
+        );
+        // Theme fonts are not resolved.
+        let markdown_doc = MarkdownDocument::from_file("./test/headers.docx").unwrap();
+        assert!(markdown_doc.fonts_used().is_empty());
+    }
+
+    #[test]
+    fn test_symbol_font_bullets() {
+        let markdown_doc = MarkdownDocument::from_file("./test/wingdings_bullets.docx").unwrap();
+        assert_eq!(
+            markdown_doc.numberings[&1].levels[0].font.as_deref(),
+            Some("Wingdings")
+        );
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.contains("- Write the report\n"));
+        assert!(markdown.contains("- Blue\n"));
+
+        let markdown = markdown_doc.to_markdown_with(&MarkdownOptions {
+            preserve_bullet_glyphs: true,
+            ..Default::default()
+        });
+        assert!(markdown.contains("✓ Write the report\n\n✓ Review it\n"));
+        assert!(markdown.contains("▪ Blue\n\n▪ Green\n"));
+    }
+
+    #[test]
+    fn test_closed_atx_headings() {
+        let markdown_doc = MarkdownDocument::from_file("./test/headers.docx").unwrap();
+        let markdown = markdown_doc.to_markdown_with(&MarkdownOptions {
+            closed_atx_headings: true,
+            ..Default::default()
+        });
+        assert!(markdown
+            .starts_with("# A Test of Headers #\n\n## Second Level ##\n\nSome plain text.\n"));
+        assert!(markdown.contains("\n###### Sixth level ######\n"));
+        assert!(markdown.contains("\nSeventh level\n"));
+    }
+
+    #[test]
+    fn test_shaded_monospace_inline_code() {
+        let markdown_doc = MarkdownDocument::from_file("./test/shaded_inline_code.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert_eq!(
+            markdown,
+            "Call `parse(path)` to read the file, not Courier without shading.\n"
+        );
+    }
+
+    #[test]
+    fn test_image_json_mode() {
+        let markdown_doc = MarkdownDocument::from_file("./test/ole_object.docx").unwrap();
+        let to_json = |image_json_mode| {
+            let options = MarkdownOptions {
+                image_json_mode,
+                ..Default::default()
+            };
+            let json = markdown_doc.to_json_with(false, &options).unwrap();
+            serde_json::from_str::<serde_json::Value>(&json).unwrap()
+        };
+
+        let json = to_json(ImageJsonMode::Base64);
+        let images = json["images"].as_object().unwrap();
+        assert_eq!(images.len(), 1);
+        let image = images.values().next().unwrap().as_str().unwrap();
+        assert!(image.starts_with("data:image/png;base64,"));
+
+        let json = to_json(ImageJsonMode::Paths);
+        let images = json["images"].as_object().unwrap();
+        assert_eq!(images.len(), 1);
+        assert_eq!(images.values().next().unwrap(), "./media/image2.png");
+
+        let json = to_json(ImageJsonMode::Omit);
+        assert!(json.get("images").is_none());
+        assert!(json["content"].is_array());
+    }
+
+    #[test]
+    fn test_clear_break() {
+        let markdown_doc = MarkdownDocument::from_file("./test/clear_break.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.starts_with("Text next to a floating image.\n\nText below the image.\n\n"));
+        let markdown = markdown_doc.to_markdown_with(&MarkdownOptions {
+            image_syntax: ImageSyntax::HtmlImg,
+            ..Default::default()
+        });
+        assert!(markdown.starts_with(
+            "Text next to a floating image.<br clear=\"all\"/>Text below the image.\n\n"
+        ));
+    }
+
+    #[test]
+    fn test_block_postprocess() {
+        let markdown_doc = MarkdownDocument::from_file("./test/headers.docx").unwrap();
+        let markdown = markdown_doc.to_markdown_with(&MarkdownOptions {
+            block_postprocess: Some(BlockPostprocess::new(|_, markdown| {
+                match markdown.starts_with('#') {
+                    true => markdown.to_uppercase(),
+                    false => markdown,
+                }
+            })),
+            ..Default::default()
+        });
+        assert!(
+            markdown.starts_with("# A TEST OF HEADERS\n\n## SECOND LEVEL\n\nSome plain text.\n")
+        );
+        assert!(markdown.contains("\n###### SIXTH LEVEL\n\nSome more plain text.\n"));
+        assert!(markdown.contains("\nSeventh level\n"));
+    }
+
+    #[test]
+    fn test_blank_lines_from_spacing() {
+        let markdown_doc = MarkdownDocument::from_file("./test/wide_spacing.docx").unwrap();
+        assert_eq!(markdown_doc.default_space_after, Some(960));
+        assert_eq!(
+            markdown_doc.to_markdown(false),
+            "First paragraph.\n\nSecond paragraph.\n"
+        );
+        let options = MarkdownOptions {
+            blank_lines_from_spacing: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            markdown_doc.to_markdown_with(&options),
+            "First paragraph.\n\n\n\nSecond paragraph.\n"
+        );
+
+        // The usual 10pt adds nothing.
+        let markdown_doc = MarkdownDocument::from_file("./test/headers.docx").unwrap();
+        assert_eq!(markdown_doc.default_space_after, Some(200));
+        assert_eq!(
+            markdown_doc.to_markdown_with(&options),
+            markdown_doc.to_markdown(false)
+        );
+    }
+
+    #[test]
+    fn test_ruby() {
+        let markdown_doc = MarkdownDocument::from_file("./test/ruby.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert_eq!(markdown, "これは漢字(かんじ)です。\n");
+        let markdown = markdown_doc.to_markdown_with(&MarkdownOptions {
+            ruby_syntax: RubySyntax::Html,
+            ..Default::default()
+        });
+        assert_eq!(markdown, "これは<ruby>漢字<rt>かんじ</rt></ruby>です。\n");
+        let json = markdown_doc.to_json(false).unwrap();
+        assert!(json.contains(r#""textType":"Ruby","text":"漢字","ruby":"かんじ""#));
     }
 
     #[test]
-    fn test_one_row_table() {
-        let markdown_pandoc = fs::read_to_string("./test/table_one_row.md").unwrap();
-        let markdown_doc = MarkdownDocument::from_file("./test/table_one_row.docx").unwrap();
+    fn test_paragraph_link() {
+        let markdown_doc = MarkdownDocument::from_file("./test/paragraph_link.docx").unwrap();
         let markdown = markdown_doc.to_markdown(false);
-        assert_eq!(markdown_pandoc, markdown);
+        assert_eq!(
+            markdown,
+            "Intro.\n\n**[Download the report](https://example.com/report.pdf)**\n"
+        );
     }
 
     #[test]
-    fn test_table_with_list_cell() {
-        let markdown_pandoc = fs::read_to_string("./test/table_with_list_cell.md").unwrap();
-        let markdown_doc = MarkdownDocument::from_file("./test/table_with_list_cell.docx").unwrap();
+    fn test_exclude_styles() {
+        let options = MarkdownOptions {
+            exclude_styles: HashSet::from(["Disclaimer".to_string()]),
+            ..Default::default()
+        };
+        let expected = "# Quarterly results\n\nRevenue grew by 5%.\n";
+
+        // When converting
+        let markdown_doc = MarkdownDocument::from_file("./test/disclaimer.docx").unwrap();
+        assert!(markdown_doc
+            .to_markdown(false)
+            .contains("Do not distribute."));
+        assert_eq!(markdown_doc.to_markdown_with(&options), expected);
+
+        // When parsing
+        let markdown_doc =
+            MarkdownDocument::from_file_with("./test/disclaimer.docx", &options).unwrap();
+        assert_eq!(markdown_doc.content.len(), 2);
+        assert_eq!(markdown_doc.to_markdown(false), expected);
+    }
+
+    #[test]
+    fn test_numbered_headings_restart() {
+        let markdown_doc = MarkdownDocument::from_file("./test/numbered_headings.docx").unwrap();
+        let markdown = markdown_doc.to_markdown_with(&MarkdownOptions {
+            use_level_text: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            markdown,
+            "# 1. Introduction\n\n## 1.1. Purpose\n\n## 1.2. Scope\n\n# 2. Design\n\n## 2.1. Overview\n\n## 2.2. Details\n"
+        );
+    }
+
+    #[test]
+    fn test_cell_backgrounds() {
+        let markdown_doc = MarkdownDocument::from_file("./test/cell_shading.docx").unwrap();
+        let MarkdownContent::Table(table) = &markdown_doc.content[0] else {
+            panic!("expected a table");
+        };
+        assert_eq!(table[0].background(1), None);
+        assert_eq!(table[1].background(0), None);
+        assert_eq!(table[1].background(1), Some("FF0000"));
+        assert_eq!(table[2].background(1), Some("E97132"));
+        let json = markdown_doc.to_json(false).unwrap();
+        assert!(json.contains(r#""backgrounds":{"1":"FF0000"}"#));
+    }
+
+    #[test]
+    fn test_line_break_in_heading() {
+        let markdown_doc = MarkdownDocument::from_file("./test/heading_break.docx").unwrap();
         let markdown = markdown_doc.to_markdown(false);
-        assert_eq!(markdown_pandoc, markdown);
+        assert!(markdown.starts_with("# Annual Report 2024\n\n"));
     }
 
     #[test]
-    fn test_tables_separated_with_rawblock() {
-        let markdown_pandoc =
-            fs::read_to_string("./test/tables_separated_with_rawblock.md").unwrap();
+    fn test_contextual_spacing_lists() {
+        let markdown_doc = MarkdownDocument::from_file("./test/contextual_spacing.docx").unwrap();
+        assert_eq!(
+            markdown_doc.styles["ListParagraph"].contextual_spacing,
+            Some(true)
+        );
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.contains("- Apples\n- Pears\n- Plums\n\nChores\n"));
+        assert!(markdown.contains("- Wash up\n\n- Sweep\n"));
+    }
+
+    #[test]
+    fn test_vml_image() {
+        let markdown_doc = MarkdownDocument::from_file("./test/vml_image.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.contains("![Company logo](./media/logo.png)"));
+        assert!(markdown_doc
+            .images
+            .keys()
+            .any(|key| key.ends_with("media/logo.png")));
+        let MarkdownContent::Paragraph(paragraph) = &markdown_doc.content[1] else {
+            panic!("expected a paragraph");
+        };
+        let image = paragraph.blocks[0].image.as_ref().unwrap();
+        assert_eq!((image.width, image.height), (Some(160), Some(80)));
+    }
+
+    #[test]
+    fn test_markdown_checked() {
+        let markdown_doc = MarkdownDocument::from_file("./test/tables.docx").unwrap();
+        assert!(markdown_doc
+            .to_markdown_checked(&MarkdownOptions::default())
+            .is_ok());
+
+        let err = markdown_doc
+            .to_markdown_checked(&MarkdownOptions {
+                cell_paragraph_join: CellParagraphJoin::Newline,
+                ..Default::default()
+            })
+            .unwrap_err();
+        assert!(err
+            .violations
+            .iter()
+            .any(|violation| violation.message.starts_with("table row has")));
+
+        let err = markdown_doc
+            .to_markdown_checked(&MarkdownOptions {
+                strict: true,
+                ..Default::default()
+            })
+            .unwrap_err();
+        assert!(err
+            .violations
+            .iter()
+            .any(|violation| violation.message == "raw HTML `<br/>`"));
+    }
+
+    #[test]
+    fn test_bidi_isolates() {
+        let markdown_doc = MarkdownDocument::from_file("./test/bidi_runs.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(
+            markdown.contains("The greeting \u{2067}مرحبا بالعالم\u{2069} means hello world.\n")
+        );
+        assert!(markdown.contains("اسم الملف هو \u{2066}report.docx\u{2069}\n"));
+    }
+
+    #[test]
+    fn test_font_sizes() {
+        let style = |size| BlockStyle {
+            size: Some(size),
+            ..BlockStyle::new()
+        };
+        assert_eq!(style(19).size_in_points(), Some(9.5));
+        assert_eq!(style(21).size_in_points(), Some(10.5));
+        assert_eq!(style(21).size_name(), Some("s"));
+        assert_eq!(style(22).size_name(), Some("m"));
+        assert_eq!(style(72).size_name(), Some("xxl"));
+        assert_eq!(BlockStyle::new().size_in_points(), None);
+
+        let options = MarkdownOptions {
+            keep_empty_paragraphs: true,
+            ..Default::default()
+        };
         let markdown_doc =
-            MarkdownDocument::from_file("./test/tables_separated_with_rawblock.docx").unwrap();
+            MarkdownDocument::from_file_with("./test/empty_paragraphs.docx", &options).unwrap();
+        let to_json = |font_size_json| {
+            markdown_doc
+                .to_json_with(
+                    false,
+                    &MarkdownOptions {
+                        font_size_json,
+                        ..Default::default()
+                    },
+                )
+                .unwrap()
+        };
+        assert!(to_json(FontSizeJson::HalfPoints).contains(r#""size":72"#));
+        assert!(to_json(FontSizeJson::Points).contains(r#""size":36.0"#));
+        assert!(to_json(FontSizeJson::Named).contains(r#""size":"xxl""#));
+    }
+
+    #[test]
+    fn test_tables_as_csv() {
+        let markdown_doc = MarkdownDocument::from_file("./test/tables_csv.docx").unwrap();
+        assert_eq!(
+            markdown_doc.tables_as_csv(),
+            vec![concat!(
+                "City,Note\r\n",
+                "\"Paris, France\",\"Known as \"\"the City of Light\"\"\"\r\n",
+                "Berlin,Capital since 1990\r\n",
+            )]
+        );
+    }
+
+    #[test]
+    fn test_many_links() {
+        let markdown_doc = MarkdownDocument::from_file("./test/many_links.docx").unwrap();
+        let links: Vec<&TextBlock> = markdown_doc
+            .paragraphs()
+            .flat_map(|paragraph| &paragraph.blocks)
+            .filter(|block| block.text_type == TextType::Link)
+            .collect();
+        assert_eq!(links.len(), 2000);
+        for (i, link) in links.iter().enumerate() {
+            assert_eq!(
+                link.text,
+                format!("[Link {i}](https://example.com/page/{i})")
+            );
+            assert_eq!(link.target_mode.as_deref(), Some("External"));
+        }
+    }
+
+    #[test]
+    fn test_outline_level_as_heading() {
+        let markdown_doc = MarkdownDocument::from_file("./test/outline_level_body.docx").unwrap();
         let markdown = markdown_doc.to_markdown(false);
-        assert_eq!(markdown_pandoc, markdown);
+        assert!(markdown.contains("# Results\n\n## Key findings\n\n"));
+
+        let markdown = markdown_doc.to_markdown_with(&MarkdownOptions {
+            outline_level_as_heading: false,
+            ..Default::default()
+        });
+        assert!(markdown.contains("# Results\n\nKey findings\n\nSales grew"));
+        let json = markdown_doc.to_json(false).unwrap();
+        assert!(json.contains(r#""outlineLvl":1"#));
+    }
+
+    #[test]
+    fn test_smart_art() {
+        let markdown_doc = MarkdownDocument::from_file("./test/smart_art.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.contains(concat!(
+            "Our process\n\n",
+            "- Plan\n",
+            "    - Gather requirements\n",
+            "- Build\n",
+            "    - Write code\n",
+            "    - Run tests\n",
+            "\nThat is all.\n",
+        )));
+        let json = markdown_doc.to_json(false).unwrap();
+        assert!(json.contains(
+            r#"{"smartArt":{"nodes":[{"text":"Plan","children":[{"text":"Gather requirements"}]}"#
+        ));
+        assert!(markdown_doc.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_tight_paragraph_spacing() {
+        let options = MarkdownOptions {
+            paragraph_spacing: ParagraphSpacing::Tight,
+            ..Default::default()
+        };
+        let markdown_doc = MarkdownDocument::from_file("./test/outline_level_body.docx").unwrap();
+        assert_eq!(
+            markdown_doc.to_markdown_with(&options),
+            "# Results\n## Key findings\nSales grew in every region.\n"
+        );
+        let markdown_doc =
+            MarkdownDocument::from_file("./test/ole_object_no_preview.docx").unwrap();
+        assert_eq!(
+            markdown_doc.to_markdown_with(&options),
+            "An attachment without a preview:\nThe end.\n"
+        );
+
+        // Lists stay apart from the paragraphs around them.
+        let markdown_doc = MarkdownDocument::from_file("./test/contextual_spacing.docx").unwrap();
+        let markdown = markdown_doc.to_markdown_with(&options);
+        assert!(markdown.starts_with("Shopping list\n\n- Apples\n"));
+        assert!(markdown.contains("- Plums\n\nChores\n\n- Wash up\n"));
+    }
+
+    #[test]
+    fn test_revision_timeline() {
+        let markdown_doc = MarkdownDocument::from_file("./test/revision_dates.docx").unwrap();
+        let timeline = markdown_doc.revision_timeline();
+        let events: Vec<(RevisionKind, &str, &str, &str)> = timeline
+            .iter()
+            .map(|event| {
+                (
+                    event.kind,
+                    event.author.as_deref().unwrap_or_default(),
+                    event.date.as_deref().unwrap_or_default(),
+                    event.text.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                (
+                    RevisionKind::Insertion,
+                    "Alan Turing",
+                    "2024-03-01T14:00:00Z",
+                    "Approved by the board."
+                ),
+                (
+                    RevisionKind::Deletion,
+                    "Alan Turing",
+                    "2024-03-02T08:00:00Z",
+                    "a draft"
+                ),
+                (
+                    RevisionKind::Comment,
+                    "Grace Hopper",
+                    "2024-03-04T12:00:00Z",
+                    "Please check the figures."
+                ),
+                (
+                    RevisionKind::Insertion,
+                    "Grace Hopper",
+                    "2024-03-05T09:30:00Z",
+                    "final"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sniff_image_mime_type() {
+        let markdown_doc = MarkdownDocument::from_file("./test/image_no_extension.docx").unwrap();
+        let json = markdown_doc.to_json(false).unwrap();
+        assert!(json.contains("data:image/png;base64,"));
+
+        let (markdown, manifest) =
+            markdown_doc.to_markdown_with_manifest(&MarkdownOptions::default());
+        assert!(markdown.contains("![Logo](./media/image1)"));
+        assert_eq!(manifest[0].mime_type, Some("image/png"));
+    }
+
+    #[test]
+    fn test_cross_reference() {
+        let markdown_doc = MarkdownDocument::from_file("./test/cross_reference.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.contains("See [Table 1](#table-1) for the numbers."));
+        assert!(markdown.contains(r#"<a name="table-1"></a>Table 1: Sales by region"#));
+    }
+
+    #[test]
+    fn test_extract_text() {
+        let file = File::open("./test/tables_csv.docx").unwrap();
+        let text = MarkdownDocument::extract_text(file).unwrap();
+        assert_eq!(
+            text,
+            "Cities\nCity\tNote\nParis, France\tKnown as \"the City of Light\"\nBerlin\tCapital since 1990\n"
+        );
+        let markdown_doc = MarkdownDocument::from_file("./test/tables_csv.docx").unwrap();
+        assert_eq!(text, markdown_doc.to_plain_text());
+    }
+
+    #[test]
+    fn test_leading_tabs() {
+        let markdown_doc = MarkdownDocument::from_file("./test/leading_tabs.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(!markdown.contains('\t'));
+        assert!(!markdown.contains("\n    "));
+        assert!(markdown.contains("# 1. Introduction\n"));
+        assert!(markdown.contains("\n\nAfter a tab\n"));
+        assert!(markdown.contains("\n\nIndented with a tab character\n"));
+        assert!(markdown.contains("\n\nIndented with spaces\n"));
+        assert!(markdown.contains("- First item\n"));
+    }
+
+    #[test]
+    fn test_style_overrides() {
+        let markdown_doc = MarkdownDocument::from_file("./test/style_overrides.docx").unwrap();
+        let style_overrides: HashMap<String, StyleOverride> = serde_json::from_str(
+            r#"{
+                "SourceCode": { "block": "code" },
+                "BlockText": { "block": "quote", "marker": "**Note:** " },
+                "Heading1": { "headingLevel": 2 }
+            }"#,
+        )
+        .unwrap();
+        let options = MarkdownOptions {
+            style_overrides,
+            ..Default::default()
+        };
+        let markdown = markdown_doc.to_markdown_with(&options);
+        assert!(markdown.contains("## Example\n"));
+        assert!(markdown.contains("Run it like this:\n\n```\ncargo run -- input.docx\n```\n"));
+        assert!(markdown.contains("> **Note:** Keep this in mind.\n"));
+
+        // Code blocks and headings of the overrides aren't wrapped.
+        let markdown = markdown_doc.to_markdown_with(&MarkdownOptions {
+            wrap_width: Some(10),
+            ..options
+        });
+        assert!(markdown.contains("## Example\n"));
+        assert!(markdown.contains("Run it\nlike this:\n\n```\ncargo run -- input.docx\n```\n"));
+    }
+
+    #[test]
+    fn test_nested_list_indent() {
+        let markdown_doc = MarkdownDocument::from_file("./test/lists_nested.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(
+            markdown.contains("- Fruit\n\n    - Apple\n\n        - Granny Smith\n\n- Vegetables\n")
+        );
+        // Nested items are indented by at least the content offset of their parent item.
+        let markdown = markdown_doc.to_markdown_with(&MarkdownOptions {
+            use_level_text: true,
+            ..Default::default()
+        });
+        assert!(markdown
+            .contains("1. Scope\n\n    1.1. Products\n\n         1.1.1. Hardware\n\n2. Terms\n"));
+    }
+
+    #[test]
+    fn test_linked_character_styles() {
+        let markdown_doc = MarkdownDocument::from_file("./test/linked_styles.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.contains("Mind the ***gap*** please."));
+        assert!(markdown.contains("Also ~~***this***~~."));
+    }
+
+    #[test]
+    fn test_escape_special_characters() {
+        let markdown_doc = MarkdownDocument::from_file("./test/special_characters.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.contains(r"Call a\_b\_c with 1\*2\*3 and \`quotes\`."));
+        assert!(markdown.contains("\n\\# Not a heading\n"));
+        assert!(markdown.contains(r"| a \| b "));
+        assert_eq!(
+            markdown_doc.to_plain_text().lines().next(),
+            Some("Call a_b_c with 1*2*3 and `quotes`.")
+        );
+
+        // Only the pipes of the text are escaped, not those of a link target.
+        let mut paragraph = MarkdownParagraph::new();
+        paragraph.blocks = vec![
+            TextBlock::new("x | y ".to_string(), None, TextType::Text),
+            TextBlock::new(
+                "[query](https://example.com/?q=a|b)".to_string(),
+                None,
+                TextType::Link,
+            ),
+        ];
+        let mut markdown_doc = MarkdownDocument::new();
+        markdown_doc
+            .content
+            .push(MarkdownContent::Table(vec![MarkdownTableRow {
+                is_header: true,
+                cells: vec![MarkdownTableCell::new(vec![paragraph])],
+                continued: vec![],
+                backgrounds: BTreeMap::new(),
+            }]));
+        assert!(markdown_doc
+            .to_markdown(false)
+            .contains(r"| x \| y [query](https://example.com/?q=a|b) |"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(
+            MarkdownDocument::from_file("./test/missing.docx"),
+            Err(DocxParseError::Io(_))
+        ));
+        assert!(MarkdownDocument::from_file_opt("./test/missing.docx").is_none());
+        assert!(matches!(
+            MarkdownDocument::from_reader(Cursor::new(b"not a zip file".to_vec())),
+            Err(DocxParseError::Zip(_))
+        ));
+    }
+
+    #[test]
+    fn test_rst() {
+        let markdown_doc = MarkdownDocument::from_file("./test/headers.docx").unwrap();
+        let rst = markdown_doc.to_rst(&MarkdownOptions::default());
+        assert!(rst.starts_with("A Test of Headers\n=================\n\n"));
+        assert!(rst.contains("\n\nSecond Level\n------------\n\nSome plain text.\n\n"));
+        assert!(rst.contains("\n\nThird level\n~~~~~~~~~~~\n\n"));
+        assert!(rst.contains("\n\nSixth level\n'''''''''''\n\n"));
+
+        let markdown_doc = MarkdownDocument::from_file("./test/tables.docx").unwrap();
+        let rst = markdown_doc.to_rst(&MarkdownOptions::default());
+        assert!(rst.contains(concat!(
+            "+----------------+------------+-----------+-------------------+\n",
+            "| Name           | Game       | Fame      | Blame             |\n",
+            "+================+============+===========+===================+\n",
+            "| Lebron James   | Basketball | Very High | Leaving Cleveland |\n",
+        )));
+        assert!(rst.contains(concat!(
+            "+---------+--------+\n",
+            "| Sinple  | Table  |\n",
+            "+---------+--------+\n",
+        )));
+    }
+
+    #[test]
+    fn test_empty_body() {
+        let markdown_doc = MarkdownDocument::from_file("./test/empty_body.docx").unwrap();
+        assert!(markdown_doc.content.is_empty());
+        assert_eq!(markdown_doc.to_markdown(false), "");
+        assert_eq!(markdown_doc.to_plain_text(), "");
+        let file = File::open("./test/empty_body.docx").unwrap();
+        assert_eq!(MarkdownDocument::extract_text(file).unwrap(), "");
+        assert_eq!(markdown_doc.to_rst(&MarkdownOptions::default()), "");
+        let json: serde_json::Value =
+            serde_json::from_str(&markdown_doc.to_json(false).unwrap()).unwrap();
+        assert_eq!(json["content"], serde_json::json!([]));
+
+        let markdown_doc = MarkdownDocument::from_file("./test/title_only.docx").unwrap();
+        assert_eq!(markdown_doc.to_markdown(false), "# Quarterly Report\n");
+    }
+
+    #[test]
+    fn test_vertical_align() {
+        let markdown_doc = MarkdownDocument::from_file("./test/vertical_align.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert_eq!(markdown, "E = mc<sup>2</sup>\n\nH<sub>2</sub>O\n");
+        let json = markdown_doc.to_json(false).unwrap();
+        assert!(json.contains(r#""style":{"bold":false,"italics":false,"underline":false,"strike":false,"superscript":true},"text":"2""#));
+    }
+
+    #[test]
+    fn test_colors() {
+        let markdown_doc = MarkdownDocument::from_file("./test/colors.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert_eq!(
+            markdown,
+            r#"Status: <span style="color:#FF0000">draft</span> and <span style="background:#FFFF00">to review</span>
+
+<span style="color:#0000FF">Blue </span><span style="color:#00FF00;background:#C0C0C0">both</span>
+"#
+        );
+        let json = markdown_doc.to_json(false).unwrap();
+        assert!(json.contains(r#""color":"FF0000"},"text":"draft""#));
+        assert!(json.contains(r#""highlight":"yellow"},"text":"to review""#));
+    }
+
+    #[test]
+    fn test_list_indent() {
+        let markdown_doc = MarkdownDocument::from_file("./test/lists_nested.docx").unwrap();
+        let render = |list_indent, use_level_text| {
+            markdown_doc.to_markdown_with(&MarkdownOptions {
+                list_indent,
+                use_level_text,
+                ..Default::default()
+            })
+        };
+        let markdown = render(ListIndent::Spaces(2), false);
+        assert!(markdown.contains("- Fruit\n\n  - Apple\n\n    - Granny Smith\n\n- Vegetables\n"));
+        let markdown = render(ListIndent::Spaces(2), true);
+        assert!(markdown.contains("1. Scope\n\n   1.1. Products\n\n        1.1.1. Hardware\n"));
+        let markdown = render(ListIndent::MarkerWidth, false);
+        assert!(markdown.contains("- Fruit\n\n  - Apple\n\n    - Granny Smith\n\n- Vegetables\n"));
+        let markdown = render(ListIndent::MarkerWidth, true);
+        assert!(markdown.contains("1. Scope\n\n   1.1. Products\n\n        1.1.1. Hardware\n"));
+        let markdown = render(ListIndent::Tabs, false);
+        assert!(markdown.contains("- Fruit\n\n\t- Apple\n\n\t\t- Granny Smith\n\n- Vegetables\n"));
+    }
+
+    #[test]
+    fn test_all_hyperlink_targets() {
+        let markdown_doc = MarkdownDocument::from_file("./test/orphaned_hyperlinks.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.contains("See [the docs](https://example.com/docs)."));
+        assert!(!markdown.contains("removed"));
+        assert_eq!(
+            markdown_doc.all_hyperlink_targets(),
+            vec!["https://example.com/docs", "https://example.com/removed"]
+        );
+    }
+
+    #[test]
+    fn test_heading_styles_without_outline_level() {
+        let markdown_doc = MarkdownDocument::from_file("./test/heading_styles.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(
+            markdown.contains("# Introduction\n\nSome text.\n\n## Background\n\n### Details\n\n")
+        );
+    }
+
+    #[test]
+    fn test_image_dir() {
+        let markdown_doc = MarkdownDocument::from_file("./test/ole_object.docx").unwrap();
+        let image_dir = std::env::temp_dir().join("docx-parser-test-image-dir");
+        let options = MarkdownOptions {
+            export_images: true,
+            image_dir: Some(image_dir.clone()),
+            image_prefix: Some("report-".to_string()),
+            ..Default::default()
+        };
+        let markdown = markdown_doc.to_markdown_with_options(&options).unwrap();
+        let path = image_dir.join("report-image2.png");
+        assert!(markdown.contains(&format!(
+            "![Embedded object: Excel.Sheet.12]({})",
+            path.display()
+        )));
+        assert!(path.exists());
+        std::fs::remove_dir_all(&image_dir).unwrap();
+
+        // A file where the directory should be.
+        let image_dir = std::env::temp_dir().join("docx-parser-test-image-file");
+        std::fs::write(&image_dir, b"").unwrap();
+        let options = MarkdownOptions {
+            image_dir: Some(image_dir.clone()),
+            ..options
+        };
+        assert!(markdown_doc.to_markdown_with_options(&options).is_err());
+        std::fs::remove_file(&image_dir).unwrap();
+    }
+
+    #[test]
+    fn test_base64_images() {
+        let markdown_doc = MarkdownDocument::from_file("./test/ole_object.docx").unwrap();
+        let markdown = markdown_doc.to_markdown_with(&MarkdownOptions {
+            base64_images: true,
+            ..Default::default()
+        });
+        assert!(markdown
+            .contains("![Embedded object: Excel.Sheet.12](data:image/png;base64,iVBORw0KGgo"));
+
+        // An image that isn't in the package keeps its link.
+        let block = TextBlock::new(
+            "![Missing](./media/image9.png)".to_string(),
+            None,
+            TextType::Image,
+        );
+        assert_eq!(
+            markdown_doc.inline_image(&block).text,
+            "![Missing](./media/image9.png)"
+        );
+    }
+
+    #[test]
+    fn test_line_breaks() {
+        let markdown_doc = MarkdownDocument::from_file("./test/line_breaks.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert_eq!(
+            markdown,
+            "221B Baker Street  \nLondon  \nNW1 6XE  \nUnited Kingdom\n\nNext page\n"
+        );
+        assert_eq!(
+            markdown_doc.to_plain_text(),
+            "221B Baker Street\nLondon\nNW1 6XE\nUnited Kingdom\nNext page"
+        );
+        let file = File::open("./test/line_breaks.docx").unwrap();
+        let text = MarkdownDocument::extract_text(file).unwrap();
+        assert_eq!(text, markdown_doc.to_plain_text());
+        let json = markdown_doc.to_json(false).unwrap();
+        assert!(json.contains(r#""pageBreakBefore":true"#));
+    }
+
+    #[test]
+    fn test_tab_replacement() {
+        let markdown_doc = MarkdownDocument::from_file("./test/tabs.docx").unwrap();
+        assert_eq!(markdown_doc.to_markdown(false), "Name:    John\n");
+        let markdown = markdown_doc.to_markdown_with(&MarkdownOptions {
+            tab_replacement: "\t".to_string(),
+            ..Default::default()
+        });
+        assert_eq!(markdown, "Name:\tJohn\n");
+        assert_eq!(markdown_doc.to_plain_text(), "Name:\tJohn");
+    }
+
+    #[test]
+    fn test_merged_cells() {
+        let markdown_doc = MarkdownDocument::from_file("./test/merged_cells.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        let rows: Vec<&str> = markdown
+            .lines()
+            .filter(|line| line.starts_with('|'))
+            .collect();
+        assert_eq!(rows.len(), 5);
+        assert!(rows.iter().all(|row| row.matches('|').count() == 5));
+        assert_eq!(rows[0].matches("Name").count(), 2);
+        assert_eq!(rows[0].matches("Score").count(), 2);
+        let continuation: Vec<&str> = rows[4].split('|').map(str::trim).collect();
+        assert_eq!(continuation, vec!["", "", "King", "8", "6", ""]);
+
+        let MarkdownContent::Table(table) = &markdown_doc.content[0] else {
+            panic!("expected a table");
+        };
+        assert_eq!(table[0].cells[0].grid_span, Some(2));
+        assert_eq!(table[0].columns(), 4);
+        assert_eq!(table[2].cells[0].v_merge, Some(VMergeKind::Restart));
+        assert_eq!(table[3].cells[0].v_merge, Some(VMergeKind::Continue));
+    }
+
+    #[test]
+    fn test_table_alignment() {
+        let markdown_doc = MarkdownDocument::from_file("./test/table_alignment.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.contains("| Item   | Unit | Price |\n| ------ | :--: | ----: |\n"));
+
+        let options = MarkdownOptions {
+            pad_table_divider: false,
+            ..Default::default()
+        };
+        let markdown = markdown_doc.to_markdown_with(&options);
+        assert!(markdown.contains("| --- | :-: | --: |\n"));
+    }
+
+    #[test]
+    fn test_footnotes_and_endnotes() {
+        let markdown_doc = MarkdownDocument::from_file("./test/footnotes_endnotes.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert_eq!(
+            markdown,
+            "Claim.[^1] Source.[^i] Another claim.[^2]\n\n[^1]: First footnote.\n\n[^i]: An endnote.\n\n[^2]: Second footnote.\n"
+        );
+        let kinds: Vec<NoteKind> = markdown_doc.notes.iter().map(|note| note.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![NoteKind::Footnote, NoteKind::Endnote, NoteKind::Footnote]
+        );
+    }
+
+    #[test]
+    fn test_comments() {
+        let markdown_doc = MarkdownDocument::from_file("./test/comments.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.starts_with("I want some text to have a comment on it.\n"));
+
+        let comment = &markdown_doc.comments[1];
+        assert_eq!(comment.author.as_deref(), Some("Jesse Rosenthal"));
+        assert_eq!(comment.date.as_deref(), Some("2016-05-09T16:13:00Z"));
+        assert_eq!(comment.range_text, "a new paragraph.\nAnd so");
+        assert_eq!(comment.text, "A comment across paragraphs.");
+
+        let options = MarkdownOptions {
+            inline_comments: true,
+            ..Default::default()
+        };
+        let markdown = markdown_doc.to_markdown_with(&options);
+        assert!(markdown.starts_with(
+            "I want some text to have a comment <!-- comment by Jesse Rosenthal: I left a comment. -->on it.\n"
+        ));
+        let json = markdown_doc.to_json(false).unwrap();
+        assert!(json.contains(r#""rangeText":"some text to have a comment""#));
+
+        let reference = TextBlock::new(
+            "comment by A: wait -- or --- not".to_string(),
+            None,
+            TextType::CommentReference,
+        );
+        assert_eq!(
+            reference.to_markdown(&ParagraphStyle::new(), &options),
+            "<!-- comment by A: wait - - or - - - not -->"
+        );
+    }
+
+    #[test]
+    fn test_track_changes_modes() {
+        let parse = |track_changes| {
+            let options = MarkdownOptions {
+                track_changes,
+                ..Default::default()
+            };
+            MarkdownDocument::from_file_with("./test/tracked_changes.docx", &options)
+                .unwrap()
+                .to_markdown(false)
+        };
+        assert_eq!(
+            parse(TrackChanges::AcceptAll),
+            "The engine is ready. It ships in May.\n"
+        );
+        assert_eq!(parse(TrackChanges::RejectAll), "The new engine is ready.\n");
+        assert_eq!(
+            parse(TrackChanges::HtmlMarkup),
+            "The<del data-author=\"Charles Babbage\"> new</del> engine is ready.<ins data-author=\"Ada Lovelace\"> It ships in May.</ins>\n"
+        );
+    }
+
+    #[test]
+    fn test_headers_and_footers() {
+        let markdown_doc = MarkdownDocument::from_file("./test/header_footer.docx").unwrap();
+        assert_eq!(markdown_doc.headers.len(), 1);
+        assert_eq!(markdown_doc.footers.len(), 1);
+        assert_eq!(markdown_doc.to_markdown(false), "The report body.\n");
+
+        let options = MarkdownOptions {
+            headers_footers: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            markdown_doc.to_markdown_with(&options),
+            "Quarterly Report\n\n---\n\nThe report body.\n\n---\n\nPage {page} of the report\n"
+        );
+    }
+
+    #[test]
+    fn test_math_latex() {
+        let markdown_doc = MarkdownDocument::from_file("./test/math.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.contains("A fraction $\\frac{a}{b}$ and a power $x^{2}$ inline.\n"));
+        assert!(markdown.contains("$$E=mc^{2}$$\n"));
+    }
+
+    #[test]
+    fn test_options_builder() {
+        let options = MarkdownOptions::builder()
+            .image_dir("assets")
+            .wrap_width(60usize)
+            .tab_replacement("\t")
+            .strict(true)
+            .build();
+        assert_eq!(options.image_dir, Some(std::path::PathBuf::from("assets")));
+        assert_eq!(options.wrap_width, Some(60));
+        assert_eq!(options.tab_replacement, "\t");
+        assert!(options.strict);
+        assert!(!options.export_images);
+
+        let markdown_doc = MarkdownDocument::from_file("./test/headers.docx").unwrap();
+        assert_eq!(
+            markdown_doc.to_markdown_with(&MarkdownOptions::builder().build()),
+            markdown_doc.to_markdown(false)
+        );
+    }
+
+    #[test]
+    fn test_long_letter_and_roman_lists() {
+        let markdown_doc = MarkdownDocument::from_file("./test/long_lists.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.contains("Z. Letter item 26"));
+        assert!(markdown.contains("AA. Letter item 27"));
+        assert!(markdown.contains("AD. Letter item 30"));
+        assert!(markdown.contains("IX. Roman item 9"));
+        assert!(markdown.contains("XII. Roman item 12"));
+    }
+
+    #[test]
+    fn test_list_start_values() {
+        let markdown_doc =
+            MarkdownDocument::from_file("./test/lists_reusing_numbering.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert_eq!(
+            markdown,
+            "1. Preheat the oven\n\n2. Mix the flour\n\n    a. Sift it first\n\n3. Bake\n\nThen clean up:\n\n1. Wash the bowl\n\n2. Dry the bowl\n"
+        );
+
+        let markdown_doc = MarkdownDocument::from_file("./test/lists_restarting.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.starts_with("2. Foo\n\n3. Bar\n\n4. Baz\n"));
+        assert!(markdown.contains("1. Bop."));
+
+        let markdown_doc = MarkdownDocument::from_file("./test/lists_continuing.docx").unwrap();
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.contains("4. Bop"));
+    }
+
+    #[test]
+    fn test_outline_numbering() {
+        let markdown_doc = MarkdownDocument::from_file("./test/lists_outline.docx").unwrap();
+        let options = MarkdownOptions {
+            use_level_text: true,
+            list_indent: ListIndent::MarkerWidth,
+            ..Default::default()
+        };
+        assert_eq!(
+            markdown_doc.to_markdown_with(&options),
+            "1. Definitions\n\n   1.1 Terms\n\n       1.1.1 Goods\n\n       1.1.2 Services\n\n   1.2 Parties\n\n2. Obligations\n\n   2.1 Payment\n\n       2.1.1 Invoices\n"
+        );
+        // Without the level text every level is numbered on its own.
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.starts_with("1. Definitions\n\n    1. Terms\n\n        1. Goods\n"));
+    }
+
+    #[test]
+    fn test_to_html() {
+        let options = HtmlOptions::default();
+        let markdown_doc = MarkdownDocument::from_file("./test/headers.docx").unwrap();
+        let html = markdown_doc.to_html(&options);
+        assert!(html.contains("<h1>A Test of Headers</h1>\n<h2>Second Level</h2>\n"));
+        assert!(html.contains("<p>Some plain text.</p>"));
+
+        let markdown_doc = MarkdownDocument::from_file("./test/inline_formatting.docx").unwrap();
+        let html = markdown_doc.to_html(&options);
+        assert!(html.contains("<em>italics</em>"));
+        assert!(html.contains("<strong>"));
+        assert!(html.contains("<s>strikethrough</s>"));
+        assert!(html.contains("<sup>superscript</sup>"));
+        assert!(html.contains("<sub>subscript</sub>"));
+        assert!(html.contains("A line<br/>break."));
+
+        let markdown_doc =
+            MarkdownDocument::from_file("./test/lists_reusing_numbering.docx").unwrap();
+        let html = markdown_doc.to_html(&options);
+        assert!(html.starts_with(
+            "<ol>\n<li>Preheat the oven</li>\n<li>Mix the flour<ol>\n<li>Sift it first</li>\n</ol>\n</li>\n<li>Bake</li>\n</ol>\n<p>Then clean up:</p>\n"
+        ));
+        let markdown_doc = MarkdownDocument::from_file("./test/lists_restarting.docx").unwrap();
+        let html = markdown_doc.to_html(&options);
+        assert!(html.starts_with("<ol start=\"2\">\n<li>Foo</li>\n"));
+
+        let markdown_doc = MarkdownDocument::from_file("./test/merged_cells.docx").unwrap();
+        let html = markdown_doc.to_html(&options);
+        assert!(html.starts_with("<table>\n<tr>"));
+        assert!(html.contains(" colspan=\"2\">"));
+        assert!(html.contains(" rowspan=\""));
+        assert_eq!(html.matches("<tr>").count(), 4);
+        assert!(html.contains("<td rowspan=\"2\">Ada</td>"));
+
+        let markdown_doc = MarkdownDocument::from_file("./test/ole_object.docx").unwrap();
+        let html = markdown_doc.to_html(&HtmlOptions {
+            markdown: MarkdownOptions {
+                base64_images: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        assert!(html.contains("<img src=\"data:image/png;base64,iVBORw0KGgo"));
+
+        let options = HtmlOptions {
+            standalone: true,
+            ..Default::default()
+        };
+        let html = markdown_doc.to_html(&options);
+        assert!(html.starts_with("<!DOCTYPE html>\n<html"));
+        assert!(html.ends_with("</body>\n</html>\n"));
+    }
+
+    #[test]
+    fn test_stream_markdown() {
+        let options = MarkdownOptions {
+            headers_footers: true,
+            ..Default::default()
+        };
+        for (file, expected) in [
+            (
+                "./test/lists_reusing_numbering.docx",
+                "1. Preheat the oven\n\n2. Mix the flour\n\n    a. Sift it first\n\n3. Bake\n\nThen clean up:\n\n1. Wash the bowl\n\n2. Dry the bowl\n",
+            ),
+            (
+                "./test/merged_cells.docx",
+                concat!(
+                    "| Name  | Name     | Score | Score |\n",
+                    "| ----- | -------- | ----- | ----- |\n",
+                    "| First | Last     | Math  | Art   |\n",
+                    "| Ada   | Lovelace | 9     | 7     |\n",
+                    "|       | King     | 8     | 6     |\n",
+                ),
+            ),
+            (
+                "./test/footnotes_endnotes.docx",
+                "Claim.[^1] Source.[^i] Another claim.[^2]\n\n[^1]: First footnote.\n\n[^i]: An endnote.\n\n[^2]: Second footnote.\n",
+            ),
+            (
+                "./test/header_footer.docx",
+                "Quarterly Report\n\n---\n\nThe report body.\n\n---\n\nPage {page} of the report\n",
+            ),
+        ] {
+            let markdown_doc = MarkdownDocument::from_file(file).unwrap();
+            let mut markdown = vec![];
+            markdown_doc
+                .stream_markdown(&mut markdown, &options)
+                .unwrap();
+            assert_eq!(String::from_utf8(markdown).unwrap(), expected, "{file}");
+            assert_eq!(
+                markdown_doc.blocks_iter().count(),
+                markdown_doc.content.len()
+            );
+        }
+
+        // An image that can't be exported is an error, with a file where the directory should be.
+        let markdown_doc = MarkdownDocument::from_file("./test/ole_object.docx").unwrap();
+        let image_dir = std::env::temp_dir().join("docx-parser-test-stream-image-file");
+        std::fs::write(&image_dir, b"").unwrap();
+        let options = MarkdownOptions {
+            export_images: true,
+            image_dir: Some(image_dir.clone()),
+            ..Default::default()
+        };
+        assert!(markdown_doc.stream_markdown(&mut vec![], &options).is_err());
+        std::fs::remove_file(&image_dir).unwrap();
+    }
+
+    #[test]
+    fn test_runs_not_merged_across_breaks() {
+        let markdown_doc = MarkdownDocument::from_file("./test/run_separators.docx").unwrap();
+        let paragraphs: Vec<&MarkdownParagraph> = markdown_doc
+            .content
+            .iter()
+            .filter_map(|content| match content {
+                MarkdownContent::Paragraph(paragraph) => Some(paragraph.as_ref()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(paragraphs.len(), 6);
+        for paragraph in &paragraphs[..4] {
+            let blocks: Vec<(&str, &TextType)> = paragraph
+                .blocks
+                .iter()
+                .map(|block| (block.text.as_str(), &block.text_type))
+                .collect();
+            assert_eq!(
+                blocks,
+                vec![
+                    ("foo", &TextType::Text),
+                    ("", &TextType::LineBreak),
+                    ("bar", &TextType::Text)
+                ]
+            );
+        }
+        // A page break at the end of the paragraph adds no line break.
+        assert_eq!(paragraphs[4].blocks.len(), 1);
+        // Runs without anything in between are still merged.
+        assert_eq!(paragraphs[5].blocks.len(), 1);
+        assert_eq!(paragraphs[5].blocks[0].text, "foobar");
+
+        let markdown = markdown_doc.to_markdown(false);
+        assert!(markdown.starts_with("foo  \nbar\n\nfoo  \nbar\n\nfoo  \nbar\n\nfoo  \nbar\n"));
+        assert!(markdown.ends_with("Last on the page\n\nfoobar\n"));
+
+        let file = File::open("./test/run_separators.docx").unwrap();
+        assert_eq!(
+            MarkdownDocument::extract_text(file).unwrap(),
+            markdown_doc.to_plain_text()
+        );
     }
 }
@@ -0,0 +1,246 @@
+//! A lightweight check of the generated markdown, see
+//! [`MarkdownDocument::to_markdown_checked`](crate::MarkdownDocument::to_markdown_checked).
+
+use std::fmt;
+
+/// Emphasis markers that have to come in pairs.
+const PAIRED_MARKERS: [&str; 3] = ["**", "__", "~~"];
+
+/// A problem in the generated markdown.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintViolation {
+    /// The line of the markdown, starting at 1
+    pub line: usize,
+    pub message: String,
+}
+
+/// The markdown that [`MarkdownDocument::to_markdown_checked`](crate::MarkdownDocument::to_markdown_checked)
+/// found to be invalid, with the problems in it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkdownLintError {
+    pub violations: Vec<LintViolation>,
+}
+
+impl fmt::Display for LintViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl fmt::Display for MarkdownLintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid markdown")?;
+        for violation in &self.violations {
+            write!(f, "\n{violation}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for MarkdownLintError {}
+
+/// Check markdown for unbalanced emphasis, malformed pipe tables and, unless `allow_html`, raw
+/// HTML.
+pub fn lint(markdown: &str, allow_html: bool) -> Vec<LintViolation> {
+    let mut violations = vec![];
+    let mut violation = |line: usize, message: String| {
+        violations.push(LintViolation {
+            line: line + 1,
+            message,
+        })
+    };
+
+    let lines: Vec<&str> = markdown.lines().collect();
+    let mut in_code_block = false;
+    // The text of the current paragraph, without code spans, and the line it starts at.
+    let mut paragraph: Option<(usize, String)> = None;
+    // The number of cells of the current table, from its header row.
+    let mut table_columns: Option<usize> = None;
+    let mut table_start = 0;
+
+    for (i, line) in lines.iter().enumerate() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+        let text = without_code_spans(line);
+
+        if line.starts_with('|') {
+            let cells = table_cells(line);
+            match table_columns {
+                None => {
+                    table_start = i;
+                    table_columns = Some(cells.len());
+                }
+                Some(columns) if i == table_start + 1 => {
+                    if !cells.iter().all(|cell| is_delimiter_cell(cell)) {
+                        violation(i, "table without a delimiter row".to_string());
+                    } else if cells.len() != columns {
+                        violation(
+                            i,
+                            format!(
+                                "table delimiter row has {} cells, not {columns}",
+                                cells.len()
+                            ),
+                        );
+                    }
+                }
+                Some(columns) if cells.len() != columns => {
+                    violation(
+                        i,
+                        format!("table row has {} cells, not {columns}", cells.len()),
+                    );
+                }
+                Some(_) => (),
+            }
+            if i == table_start && !lines.get(i + 1).is_some_and(|next| next.starts_with('|')) {
+                violation(i, "table without a delimiter row".to_string());
+            }
+            check_emphasis(&text, i, &mut violation);
+        } else {
+            if table_columns.take().is_some() && split_cells(&text).len() > 1 {
+                violation(i, "table row is broken across lines".to_string());
+            }
+            if line.trim().is_empty() {
+                if let Some((start, text)) = paragraph.take() {
+                    check_emphasis(&text, start, &mut violation);
+                }
+            } else {
+                let (_, paragraph_text) = paragraph.get_or_insert((i, String::new()));
+                paragraph_text.push_str(&text);
+                paragraph_text.push('\n');
+            }
+        }
+
+        if !allow_html {
+            if let Some(tag) = html_tag(&text) {
+                violation(i, format!("raw HTML `{tag}`"));
+            }
+        }
+    }
+    if let Some((start, text)) = paragraph {
+        check_emphasis(&text, start, &mut violation);
+    }
+
+    violations
+}
+
+/// Report emphasis markers without a closing marker, and `__` inside a word, which CommonMark
+/// doesn't treat as emphasis.
+fn check_emphasis(text: &str, line: usize, violation: &mut impl FnMut(usize, String)) {
+    let text = text
+        .replace("\\*", "")
+        .replace("\\_", "")
+        .replace("\\~", "");
+    for marker in PAIRED_MARKERS {
+        if text.matches(marker).count() % 2 == 1 {
+            violation(line, format!("unbalanced `{marker}`"));
+        }
+    }
+    let chars: Vec<char> = text.chars().collect();
+    let intraword = chars.windows(4).any(|window| {
+        window[0].is_alphanumeric()
+            && window[1] == '_'
+            && window[2] == '_'
+            && window[3].is_alphanumeric()
+    });
+    if intraword {
+        violation(line, "`__` inside a word is not emphasis".to_string());
+    }
+}
+
+/// The line without the content of its code spans, which isn't markdown.
+fn without_code_spans(line: &str) -> String {
+    let mut text = String::new();
+    for (i, part) in line.split('`').enumerate() {
+        if i % 2 == 0 {
+            text.push_str(part);
+        }
+    }
+    text
+}
+
+/// The cells of a table row, e.g. `| a | b |`.
+fn table_cells(line: &str) -> Vec<String> {
+    let line = line.trim();
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let line = line.strip_suffix('|').unwrap_or(line);
+    split_cells(line)
+}
+
+/// Split text at the pipes that aren't escaped.
+fn split_cells(text: &str) -> Vec<String> {
+    let mut cells = vec![String::new()];
+    let mut escaped = false;
+    for c in text.chars() {
+        if c == '|' && !escaped {
+            cells.push(String::new());
+        } else if let Some(cell) = cells.last_mut() {
+            cell.push(c);
+        }
+        escaped = c == '\\';
+    }
+    cells
+}
+
+/// Check whether a cell of a table delimiter row is dashes, optionally with colons for the
+/// alignment, e.g. `:---`.
+fn is_delimiter_cell(cell: &str) -> bool {
+    let cell = cell.trim();
+    let dashes = cell.trim_start_matches(':').trim_end_matches(':');
+    !dashes.is_empty() && dashes.chars().all(|c| c == '-')
+}
+
+/// The first HTML tag in the text, e.g. `<br/>`, ignoring autolinks like `<https://example.com>`.
+fn html_tag(text: &str) -> Option<&str> {
+    text.match_indices('<').find_map(|(start, _)| {
+        let rest = &text[start + 1..];
+        let name = rest.strip_prefix('/').unwrap_or(rest);
+        let name_len = name
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '-')
+            .unwrap_or(name.len());
+        if name_len == 0 || !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            return None;
+        }
+        let after = name[name_len..].chars().next()?;
+        if !(after.is_whitespace() || after == '>' || after == '/') {
+            return None;
+        }
+        let end = rest.find('>')?;
+        Some(&text[start..start + end + 2])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lint() {
+        assert_eq!(lint("| a | b |\n| - | - |\n| 1 | 2 |\n", false), vec![]);
+        assert_eq!(
+            lint("| a | b |\n| - | - |\n| 1 | 2 | 3 |\n", false),
+            vec![LintViolation {
+                line: 3,
+                message: "table row has 3 cells, not 2".to_string()
+            }]
+        );
+        assert_eq!(lint("Some **bold** and `__init__`\n", false), vec![]);
+        assert_eq!(lint("Some **bold\n\ntext**\n", false).len(), 2);
+        assert_eq!(lint("snake__case__name\n", false).len(), 1);
+        assert_eq!(
+            lint("First<br/>second <https://example.com>\n", true),
+            vec![]
+        );
+        assert_eq!(
+            lint("First<br/>second <https://example.com>\n", false),
+            vec![LintViolation {
+                line: 1,
+                message: "raw HTML `<br/>`".to_string()
+            }]
+        );
+    }
+}
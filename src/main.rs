@@ -1,4 +1,4 @@
-use clap::{command, Parser};
+use clap::Parser;
 use docx_parser::MarkdownDocument;
 use std::fs;
 
@@ -69,7 +69,13 @@ fn main() {
     println!("Output destination: {}", output);
     println!("Output format: {}", format);
 
-    let markdown_doc = MarkdownDocument::from_file(input_file).unwrap();
+    let markdown_doc = match MarkdownDocument::from_file(&input_file) {
+        Ok(markdown_doc) => markdown_doc,
+        Err(err) => {
+            eprintln!("Could not parse {:?}: {}", input_file, err);
+            std::process::exit(1);
+        }
+    };
     let result = if format == "md" {
         markdown_doc.to_markdown(true)
     } else if format == "json" {
@@ -0,0 +1,199 @@
+//! Office Math (OMML) equations, which docx-rust doesn't parse.
+
+use crate::package::{is_element, M_NS};
+use roxmltree::Node;
+
+/// The plain text of an equation, i.e. the content of all its `m:t` elements.
+pub fn math_text(node: Node) -> String {
+    node.descendants()
+        .filter(|descendant| is_element(descendant, M_NS, "t"))
+        .filter_map(|text| text.text())
+        .collect()
+}
+
+/// An equation as LaTeX, e.g. `\frac{a}{b}` for a fraction. Fractions, scripts, radicals, n-ary
+/// operators, delimiters, functions and accents are converted. Other constructs, e.g. matrices,
+/// are written as their plain text.
+pub fn math_latex(node: Node) -> String {
+    let name = node.tag_name().name();
+    if node.tag_name().namespace() != Some(M_NS) {
+        return String::new();
+    }
+    match name {
+        "r" => math_text(node),
+        "f" => format!(
+            "\\frac{{{}}}{{{}}}",
+            argument(node, "num"),
+            argument(node, "den")
+        ),
+        "sSup" => format!("{}^{{{}}}", argument(node, "e"), argument(node, "sup")),
+        "sSub" => format!("{}_{{{}}}", argument(node, "e"), argument(node, "sub")),
+        "sSubSup" => format!(
+            "{}_{{{}}}^{{{}}}",
+            argument(node, "e"),
+            argument(node, "sub"),
+            argument(node, "sup")
+        ),
+        "sPre" => format!(
+            "{{}}_{{{}}}^{{{}}}{}",
+            argument(node, "sub"),
+            argument(node, "sup"),
+            argument(node, "e")
+        ),
+        "rad" => match argument(node, "deg") {
+            degree if degree.is_empty() => format!("\\sqrt{{{}}}", argument(node, "e")),
+            degree => format!("\\sqrt[{degree}]{{{}}}", argument(node, "e")),
+        },
+        "nary" => {
+            let operator = match property(node, "naryPr", "chr").unwrap_or("∫") {
+                "∑" => "\\sum",
+                "∏" => "\\prod",
+                "∐" => "\\coprod",
+                "∫" => "\\int",
+                "∬" => "\\iint",
+                "∭" => "\\iiint",
+                "∮" => "\\oint",
+                "⋃" => "\\bigcup",
+                "⋂" => "\\bigcap",
+                "⋁" => "\\bigvee",
+                "⋀" => "\\bigwedge",
+                other => other,
+            };
+            let mut latex = operator.to_string();
+            for (script, marker) in [("sub", '_'), ("sup", '^')] {
+                let limit = argument(node, script);
+                if !limit.is_empty() {
+                    latex += &format!("{marker}{{{limit}}}");
+                }
+            }
+            latex + &format!(" {}", argument(node, "e"))
+        }
+        "d" => {
+            let begin = property(node, "dPr", "begChr").unwrap_or("(");
+            let end = property(node, "dPr", "endChr").unwrap_or(")");
+            let separator = property(node, "dPr", "sepChr").unwrap_or("|");
+            let elements: Vec<String> = node
+                .children()
+                .filter(|child| is_element(child, M_NS, "e"))
+                .map(math_latex)
+                .collect();
+            format!(
+                "\\left{}{}\\right{}",
+                delimiter(begin),
+                elements.join(separator),
+                delimiter(end)
+            )
+        }
+        "func" => {
+            let name = argument(node, "fName");
+            let name = match FUNCTIONS.contains(&name.as_str()) {
+                true => format!("\\{name}"),
+                false => name,
+            };
+            format!("{name}{{{}}}", argument(node, "e"))
+        }
+        "acc" => {
+            let command = match property(node, "accPr", "chr").unwrap_or("\u{302}") {
+                "\u{303}" => "tilde",
+                "\u{307}" => "dot",
+                "\u{308}" => "ddot",
+                "\u{304}" | "\u{305}" => "bar",
+                "\u{20D7}" => "vec",
+                _ => "hat",
+            };
+            format!("\\{command}{{{}}}", argument(node, "e"))
+        }
+        "bar" => format!("\\overline{{{}}}", argument(node, "e")),
+        "limLow" => format!("{}_{{{}}}", argument(node, "e"), argument(node, "lim")),
+        "limUpp" => format!("{}^{{{}}}", argument(node, "e"), argument(node, "lim")),
+        "oMath" | "oMathPara" | "e" | "num" | "den" | "sub" | "sup" | "deg" | "fName" | "lim"
+        | "box" | "borderBox" => node.children().map(math_latex).collect(),
+        _ if name.ends_with("Pr") => String::new(),
+        _ => math_text(node),
+    }
+}
+
+/// Functions that LaTeX has a command for, e.g. `\sin`.
+const FUNCTIONS: [&str; 16] = [
+    "sin", "cos", "tan", "cot", "sec", "csc", "sinh", "cosh", "tanh", "log", "ln", "exp", "lim",
+    "max", "min", "det",
+];
+
+/// The LaTeX of the argument of a construct, e.g. the `m:num` of a fraction.
+fn argument(node: Node, name: &str) -> String {
+    node.children()
+        .find(|child| is_element(child, M_NS, name))
+        .map(math_latex)
+        .unwrap_or_default()
+}
+
+/// A property of a construct, e.g. the `m:chr` of the `m:naryPr` of an n-ary operator.
+fn property<'a>(node: Node<'a, '_>, properties: &str, name: &str) -> Option<&'a str> {
+    node.children()
+        .find(|child| is_element(child, M_NS, properties))?
+        .children()
+        .find(|child| is_element(child, M_NS, name))?
+        .attribute((M_NS, "val"))
+}
+
+/// A delimiter for `\left` and `\right`, e.g. `\{` for a brace, or `.` for none.
+fn delimiter(chr: &str) -> &str {
+    match chr {
+        "" => ".",
+        "{" => "\\{",
+        "}" => "\\}",
+        other => other,
+    }
+}
+
+/// The number of a numbered equation, e.g. `1` for `(1)` or `2.3` for `(2.3)`.
+pub fn equation_number(text: &str) -> Option<&str> {
+    let number = text.trim().strip_prefix('(')?.strip_suffix(')')?.trim();
+    let is_number = !number.is_empty()
+        && number
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '.' | '-' | ','));
+    is_number.then_some(number)
+}
+
+/// Split the `\tag{..}` of a numbered equation off the equation.
+pub fn split_tag(equation: &str) -> (&str, Option<&str>) {
+    match equation
+        .strip_suffix('}')
+        .and_then(|rest| rest.rsplit_once(" \\tag{"))
+    {
+        Some((equation, tag)) => (equation, Some(tag)),
+        None => (equation, None),
+    }
+}
+
+#[test]
+fn test_math_latex() {
+    let xml = format!(
+        r#"<m:oMath xmlns:m="{M_NS}">
+            <m:f><m:num><m:r><m:t>a</m:t></m:r></m:num><m:den><m:r><m:t>b</m:t></m:r></m:den></m:f>
+            <m:r><m:t>+</m:t></m:r>
+            <m:rad><m:radPr><m:degHide m:val="1"/></m:radPr><m:deg/><m:e><m:r><m:t>x</m:t></m:r></m:e></m:rad>
+            <m:r><m:t>+</m:t></m:r>
+            <m:nary><m:naryPr><m:chr m:val="∑"/></m:naryPr>
+                <m:sub><m:r><m:t>i=1</m:t></m:r></m:sub><m:sup><m:r><m:t>n</m:t></m:r></m:sup>
+                <m:e><m:sSub><m:e><m:r><m:t>x</m:t></m:r></m:e><m:sub><m:r><m:t>i</m:t></m:r></m:sub></m:sSub></m:e>
+            </m:nary>
+        </m:oMath>"#
+    );
+    let document = roxmltree::Document::parse(&xml).unwrap();
+    assert_eq!(
+        math_latex(document.root_element()),
+        "\\frac{a}{b}+\\sqrt{x}+\\sum_{i=1}^{n} x_{i}"
+    );
+}
+
+#[test]
+fn test_equation_number() {
+    assert_eq!(equation_number("(1)"), Some("1"));
+    assert_eq!(equation_number(" ( 2.3 ) "), Some("2.3"));
+    assert_eq!(equation_number("(see above)"), None);
+    assert_eq!(equation_number("1"), None);
+    assert_eq!(split_tag("a = b \\tag{4}"), ("a = b", Some("4")));
+    assert_eq!(split_tag("a = b"), ("a = b", None));
+}
@@ -0,0 +1,149 @@
+//! Footnotes and endnotes, which are kept in separate parts of the package.
+
+use crate::package::{is_element, outer_xml, Package, W_NS};
+use crate::utils::to_roman;
+use crate::{MarkdownParagraph, ParseContext, TextType};
+use hard_xml::XmlRead;
+use roxmltree::Node;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum NoteKind {
+    Footnote,
+    Endnote,
+}
+
+impl NoteKind {
+    fn part(&self) -> &'static str {
+        match self {
+            NoteKind::Footnote => "word/footnotes.xml",
+            NoteKind::Endnote => "word/endnotes.xml",
+        }
+    }
+
+    fn element(&self) -> &'static str {
+        match self {
+            NoteKind::Footnote => "footnote",
+            NoteKind::Endnote => "endnote",
+        }
+    }
+
+    /// The label of the `number`th note of this kind. Footnotes and endnotes are numbered
+    /// separately, endnotes with roman numerals like Word does, e.g. `[^2]` and `[^ii]`.
+    pub(crate) fn label(&self, number: usize) -> String {
+        match self {
+            NoteKind::Footnote => number.to_string(),
+            NoteKind::Endnote => to_roman(number),
+        }
+    }
+
+    /// The label of each referenced note, in the order of the references.
+    pub(crate) fn labels(references: &[(NoteKind, String)]) -> Vec<String> {
+        let (mut footnotes, mut endnotes) = (0, 0);
+        references
+            .iter()
+            .map(|(kind, _)| {
+                let count = match kind {
+                    NoteKind::Footnote => &mut footnotes,
+                    NoteKind::Endnote => &mut endnotes,
+                };
+                *count += 1;
+                kind.label(*count)
+            })
+            .collect()
+    }
+
+    /// The kind of note a run child refers to, for `w:footnoteReference` and
+    /// `w:endnoteReference`.
+    pub fn from_reference(node: &Node) -> Option<Self> {
+        if is_element(node, W_NS, "footnoteReference") {
+            Some(NoteKind::Footnote)
+        } else if is_element(node, W_NS, "endnoteReference") {
+            Some(NoteKind::Endnote)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkdownNote {
+    /// The label of the note in markdown, e.g. `1` for `[^1]`
+    pub label: String,
+    pub kind: NoteKind,
+    /// The id of the note in the notes part
+    pub id: String,
+    pub paragraphs: Vec<MarkdownParagraph>,
+}
+
+/// Notes that only format the notes area, such as the separator line above the notes.
+const SPECIAL_NOTES: [&str; 3] = ["separator", "continuationSeparator", "continuationNotice"];
+
+/// Parse the referenced notes, in the order of their labels.
+pub(crate) fn parse_notes(
+    package: &Package,
+    references: &[(NoteKind, String)],
+    context: &ParseContext,
+) -> Vec<MarkdownNote> {
+    let footnotes = parse_part(package, NoteKind::Footnote);
+    let endnotes = parse_part(package, NoteKind::Endnote);
+    let labels = NoteKind::labels(references);
+    let mut notes = vec![];
+    for ((kind, id), label) in references.iter().zip(labels) {
+        let part = match kind {
+            NoteKind::Footnote => footnotes.as_ref(),
+            NoteKind::Endnote => endnotes.as_ref(),
+        };
+        let Some(note) = part.and_then(|part| {
+            part.root_element().children().find(|node| {
+                is_element(node, W_NS, kind.element())
+                    && node.attribute((W_NS, "id")) == Some(id.as_str())
+                    && !node
+                        .attribute((W_NS, "type"))
+                        .is_some_and(|ty| SPECIAL_NOTES.contains(&ty))
+            })
+        }) else {
+            continue;
+        };
+        let mut paragraphs: Vec<MarkdownParagraph> = note
+            .children()
+            .filter(|node| is_element(node, W_NS, "p"))
+            .filter_map(|raw| {
+                let paragraph = docx_rust::document::Paragraph::from_str(outer_xml(raw)).ok()?;
+                Some(MarkdownParagraph::from_paragraph(
+                    &paragraph,
+                    Some(raw),
+                    context,
+                ))
+            })
+            .collect();
+        // The note mark is usually followed by a space.
+        if let Some(first) = paragraphs
+            .first_mut()
+            .and_then(|paragraph| paragraph.blocks.first_mut())
+        {
+            if first.text_type == TextType::Text {
+                first.text = first.text.trim_start().to_string();
+            }
+        }
+        if let Some(paragraph) = paragraphs.first_mut() {
+            paragraph
+                .blocks
+                .retain(|block| !(block.text_type == TextType::Text && block.text.is_empty()));
+        }
+        notes.push(MarkdownNote {
+            label,
+            kind: *kind,
+            id: id.clone(),
+            paragraphs,
+        });
+    }
+    notes
+}
+
+fn parse_part(package: &Package, kind: NoteKind) -> Option<roxmltree::Document<'_>> {
+    package
+        .xml(kind.part())
+        .and_then(|xml| roxmltree::Document::parse(xml).ok())
+}
@@ -0,0 +1,540 @@
+//! Options that control the conversion to markdown.
+
+use crate::MarkdownContent;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Options for [`MarkdownDocument::to_markdown_with`](crate::MarkdownDocument::to_markdown_with) and
+/// [`MarkdownDocument::from_file_with`](crate::MarkdownDocument::from_file_with).
+#[derive(Debug, Clone)]
+pub struct MarkdownOptions {
+    /// Write the images of the document to disk
+    pub export_images: bool,
+    /// The directory that images are written to and linked from, e.g. `assets`. Default is
+    /// `None`, which keeps the paths of the images in the document, e.g. `./media/image1.png`.
+    pub image_dir: Option<PathBuf>,
+    /// Put before the file names of images, e.g. `report-` for `report-image1.png`. Default is
+    /// `None`, which keeps the paths of the images in the document.
+    pub image_prefix: Option<String>,
+    /// Inline images as base64 data URLs, e.g. for a single markdown file. Images that aren't in
+    /// the package keep their link. Default is false.
+    pub base64_images: bool,
+    /// How equations are rendered
+    pub math_output: MathOutput,
+    /// Minimum width of a table column, in characters. Default is 3.
+    pub table_min_column_width: usize,
+    /// Pad the divider below the table header to the column width, e.g. `| ------ |`.
+    /// When false, every divider cell is a compact `---`. Default is true.
+    pub pad_table_divider: bool,
+    /// Keep the revision save ids (`w:rsidR`, `w:rsidRPr`) of paragraphs and runs, so they appear
+    /// in the JSON output. Only used when parsing. Default is false.
+    pub track_rsids: bool,
+    /// The character used for italics, e.g. `*italic*` or `_italic_`. Default is `*`.
+    pub italic_marker: EmphasisMarker,
+    /// The character used for bold, e.g. `**bold**` or `__bold__`. Default is `*`. With `_`,
+    /// underlined text is written as `<u>underline</u>` instead of `__underline__`.
+    pub bold_marker: EmphasisMarker,
+    /// What to do with hyperlinks to local files, e.g. `file:///C:/report.docx` or
+    /// `../other.docx`. Only used when parsing. Default is to keep them.
+    pub on_file_link: FileLinkHandling,
+    /// The bullet markers for each list level, repeated for deeper levels, e.g.
+    /// `["-", "*", "+"]`. Default is `["-"]` for every level.
+    pub bullet_markers: Vec<String>,
+    /// Keep paragraphs without any content, e.g. to preserve intentional spacing. Their paragraph
+    /// mark formatting (such as the font size) is kept in the style. Only used when parsing.
+    /// Default is false.
+    pub keep_empty_paragraphs: bool,
+    /// Use the bullet glyph of the list level (e.g. `•` or `→`) instead of a markdown bullet
+    /// marker. Bullets in the Symbol and Wingdings fonts are mapped to Unicode, e.g. `✓`. Note
+    /// that such lists are no longer markdown lists. Default is false.
+    pub preserve_bullet_glyphs: bool,
+    /// Replace table cells that continue a vertical merge with this marker, e.g. `↑`, keeping the
+    /// merged content in the first row only. Default is `None`, which leaves them as they are.
+    pub vertical_merge_marker: Option<String>,
+    /// Hard-wrap paragraphs at this column, on word boundaries. Headings and tables are never
+    /// wrapped, and links, images, inline code and equations are kept on one line. Default is
+    /// `None`, no wrapping.
+    pub wrap_width: Option<usize>,
+    /// How tracked changes (`w:ins`, `w:del`) are converted, in the body as well as in tables.
+    /// Only used when parsing. Default is to accept all changes.
+    pub track_changes: TrackChanges,
+    /// How images are written. Default is `![alt](src)`. With HTML images, breaks that clear
+    /// floating images are written as `<br clear="all"/>` instead of a blank line.
+    pub image_syntax: ImageSyntax,
+    /// Text to put around runs with a character style, keyed by style id, e.g.
+    /// `"Keyboard" => ("<kbd>", "</kbd>")`. Empty by default.
+    pub run_style_map: HashMap<String, (String, String)>,
+    /// Turn paragraphs that consist of bold text into headings, even without a heading style or
+    /// outline level. The font size decides the level. Default is false.
+    pub detect_headings: bool,
+    /// A detected heading has more characters than this, so that a single short bold word, e.g.
+    /// `Note`, isn't turned into a heading. Paragraphs with a heading style or outline level are
+    /// always headings. Default is 4.
+    pub heading_min_chars: usize,
+    /// Render numbered list markers from the `lvlText` template of the list level, so that e.g.
+    /// `(%1)` becomes `(1)` and `%1.%2` becomes `1.2`. Note that such lists are no longer markdown
+    /// lists. Default is false, which renders `1.`, `a.`, etc.
+    pub use_level_text: bool,
+    /// Fail to parse documents with content that can't be converted and would be dropped, such as
+    /// floating drawings, embedded objects without a preview image or unsupported `altChunk`s.
+    /// Without it, such content is listed in [`MarkdownDocument::warnings`](crate::MarkdownDocument::warnings).
+    /// Only used when parsing, and by [`MarkdownDocument::to_markdown_checked`](crate::MarkdownDocument::to_markdown_checked)
+    /// to report raw HTML. Default is false.
+    pub strict: bool,
+    /// How the paragraphs of a table cell are joined, as a pipe table cell is a single line.
+    /// Default is `<br/>`.
+    pub cell_paragraph_join: CellParagraphJoin,
+    /// Close headings with as many `#` as they start with, e.g. `## Title ##`. Default is false.
+    pub closed_atx_headings: bool,
+    /// How the `images` of the document are written by
+    /// [`MarkdownDocument::to_json_with`](crate::MarkdownDocument::to_json_with). Default is
+    /// base64 data URLs.
+    pub image_json_mode: ImageJsonMode,
+    /// Called with each block of the document and its markdown, which is replaced by the result,
+    /// e.g. to add anchors or classes. The markdown of a paragraph doesn't end with a line break,
+    /// that of a table ends with the line break of its last row. Default is `None`.
+    pub block_postprocess: Option<BlockPostprocess>,
+    /// Separate blocks by more blank lines when the document has a large default space after
+    /// paragraphs: one more for every 24pt. Default is false, a single blank line.
+    pub blank_lines_from_spacing: bool,
+    /// How text with a phonetic guide (ruby), e.g. furigana, is written. Default is `base(ruby)`.
+    pub ruby_syntax: RubySyntax,
+    /// Leave out paragraphs with these paragraph styles, e.g. `"Disclaimer"`. Used when parsing
+    /// as well as when converting. Empty by default.
+    pub exclude_styles: HashSet<String>,
+    /// How the font sizes of block styles are written by
+    /// [`MarkdownDocument::to_json_with`](crate::MarkdownDocument::to_json_with). Default is
+    /// half-points, e.g. `19` for 9.5pt.
+    pub font_size_json: FontSizeJson,
+    /// Turn paragraphs with an outline level of their own into headings, even though their
+    /// paragraph style isn't a heading. Word uses such outline levels to include body text in the
+    /// table of contents. Without it, they stay paragraphs and keep their outline level in the
+    /// JSON. Default is true.
+    pub outline_level_as_heading: bool,
+    /// Whether consecutive paragraphs are separated by a blank line or only a line break. Lists
+    /// and tables are always separated from paragraphs by a blank line. Default is a blank line.
+    pub paragraph_spacing: ParagraphSpacing,
+    /// How paragraphs with a paragraph style are rendered, keyed by style id, taking precedence
+    /// over the style itself, e.g. `"Disclaimer" => StyleOverride { block: Some(StyleBlock::Quote),
+    /// ..Default::default() }`. A mapping file can be read with `serde_json`. Empty by default.
+    pub style_overrides: HashMap<String, StyleOverride>,
+    /// How nested list items are indented. Default is four spaces per level.
+    pub list_indent: ListIndent,
+    /// Written for each tab in the text of a paragraph, e.g. `"\t"` to keep tabs. Tabs before
+    /// the text are left out, and a tab in a heading is a single space. Default is four spaces.
+    pub tab_replacement: String,
+    /// Render comments as HTML comments where they are referenced, e.g.
+    /// `<!-- comment by Ada: Check this -->`. The comments are in the JSON either way. Default is
+    /// false.
+    pub inline_comments: bool,
+    /// Put the first page header before the content and the last page footer after it,
+    /// separated by `---` rules. Default is false.
+    pub headers_footers: bool,
+}
+
+impl MarkdownOptions {
+    /// Options that are set one at a time, starting from the defaults, e.g.
+    /// `MarkdownOptions::builder().export_images(true).image_dir("assets").build()`.
+    pub fn builder() -> MarkdownOptionsBuilder {
+        MarkdownOptionsBuilder::default()
+    }
+
+    /// The path that an image is written to and linked from, with
+    /// [`MarkdownOptions::image_dir`] and [`MarkdownOptions::image_prefix`], e.g.
+    /// `assets/report-image1.png` for `./media/image1.png`. `None` when neither is set.
+    pub(crate) fn image_path(&self, target: &str) -> Option<String> {
+        let is_inline = target.starts_with("data:");
+        if is_inline || (self.image_dir.is_none() && self.image_prefix.is_none()) {
+            return None;
+        }
+        let file_name = target.rsplit('/').next().unwrap_or(target);
+        let file_name = format!("{}{file_name}", self.image_prefix.as_deref().unwrap_or(""));
+        Some(match &self.image_dir {
+            Some(image_dir) => image_dir.join(file_name).to_string_lossy().into_owned(),
+            None => format!("./{file_name}"),
+        })
+    }
+}
+
+impl Default for MarkdownOptions {
+    fn default() -> Self {
+        MarkdownOptions {
+            export_images: false,
+            image_dir: None,
+            image_prefix: None,
+            base64_images: false,
+            math_output: MathOutput::default(),
+            table_min_column_width: 3,
+            pad_table_divider: true,
+            track_rsids: false,
+            italic_marker: EmphasisMarker::Asterisk,
+            bold_marker: EmphasisMarker::Asterisk,
+            on_file_link: FileLinkHandling::Keep,
+            bullet_markers: vec!["-".to_string()],
+            keep_empty_paragraphs: false,
+            preserve_bullet_glyphs: false,
+            vertical_merge_marker: None,
+            wrap_width: None,
+            track_changes: TrackChanges::AcceptAll,
+            image_syntax: ImageSyntax::Markdown,
+            run_style_map: HashMap::new(),
+            detect_headings: false,
+            heading_min_chars: 4,
+            use_level_text: false,
+            strict: false,
+            cell_paragraph_join: CellParagraphJoin::Br,
+            closed_atx_headings: false,
+            image_json_mode: ImageJsonMode::Base64,
+            block_postprocess: None,
+            blank_lines_from_spacing: false,
+            ruby_syntax: RubySyntax::Parentheses,
+            exclude_styles: HashSet::new(),
+            font_size_json: FontSizeJson::HalfPoints,
+            outline_level_as_heading: true,
+            paragraph_spacing: ParagraphSpacing::Loose,
+            style_overrides: HashMap::new(),
+            list_indent: ListIndent::default(),
+            tab_replacement: "    ".to_string(),
+            inline_comments: false,
+            headers_footers: false,
+        }
+    }
+}
+
+/// A builder for [`MarkdownOptions`], see [`MarkdownOptions::builder`].
+#[derive(Debug, Clone, Default)]
+pub struct MarkdownOptionsBuilder {
+    options: MarkdownOptions,
+}
+
+/// Builder methods that set an option, by field name and type.
+macro_rules! setters {
+    ($($field:ident: $ty:ty),* $(,)?) => {
+        $(
+            #[doc = concat!("Set [`MarkdownOptions::", stringify!($field), "`].")]
+            pub fn $field(mut self, $field: $ty) -> Self {
+                self.options.$field = $field;
+                self
+            }
+        )*
+    };
+}
+
+/// Builder methods that set an optional option, by field name and the type inside the `Option`.
+macro_rules! optional_setters {
+    ($($field:ident: $ty:ty),* $(,)?) => {
+        $(
+            #[doc = concat!("Set [`MarkdownOptions::", stringify!($field), "`].")]
+            pub fn $field(mut self, $field: impl Into<$ty>) -> Self {
+                self.options.$field = Some($field.into());
+                self
+            }
+        )*
+    };
+}
+
+impl MarkdownOptionsBuilder {
+    setters! {
+        export_images: bool,
+        base64_images: bool,
+        math_output: MathOutput,
+        table_min_column_width: usize,
+        pad_table_divider: bool,
+        track_rsids: bool,
+        italic_marker: EmphasisMarker,
+        bold_marker: EmphasisMarker,
+        on_file_link: FileLinkHandling,
+        bullet_markers: Vec<String>,
+        keep_empty_paragraphs: bool,
+        preserve_bullet_glyphs: bool,
+        track_changes: TrackChanges,
+        image_syntax: ImageSyntax,
+        run_style_map: HashMap<String, (String, String)>,
+        detect_headings: bool,
+        heading_min_chars: usize,
+        use_level_text: bool,
+        strict: bool,
+        cell_paragraph_join: CellParagraphJoin,
+        closed_atx_headings: bool,
+        image_json_mode: ImageJsonMode,
+        blank_lines_from_spacing: bool,
+        ruby_syntax: RubySyntax,
+        exclude_styles: HashSet<String>,
+        font_size_json: FontSizeJson,
+        outline_level_as_heading: bool,
+        paragraph_spacing: ParagraphSpacing,
+        style_overrides: HashMap<String, StyleOverride>,
+        list_indent: ListIndent,
+        inline_comments: bool,
+        headers_footers: bool,
+    }
+
+    optional_setters! {
+        image_dir: PathBuf,
+        image_prefix: String,
+        vertical_merge_marker: String,
+        wrap_width: usize,
+        block_postprocess: BlockPostprocess,
+    }
+
+    /// Set [`MarkdownOptions::tab_replacement`].
+    pub fn tab_replacement(mut self, tab_replacement: impl Into<String>) -> Self {
+        self.options.tab_replacement = tab_replacement.into();
+        self
+    }
+
+    pub fn build(self) -> MarkdownOptions {
+        self.options
+    }
+}
+
+/// Options for [`MarkdownDocument::to_plain_text_with`](crate::MarkdownDocument::to_plain_text_with).
+#[derive(Debug, Clone)]
+pub struct PlainTextOptions {
+    /// Put between paragraphs and table rows, e.g. `"\n\n"` or a form feed. Default is `"\n"`.
+    pub paragraph_separator: String,
+    /// Put between the cells of a table row. Default is a tab.
+    pub cell_separator: String,
+    /// Keep the alternative text of images. Default is true.
+    pub include_image_alt_text: bool,
+}
+
+impl Default for PlainTextOptions {
+    fn default() -> Self {
+        PlainTextOptions {
+            paragraph_separator: "\n".to_string(),
+            cell_separator: "\t".to_string(),
+            include_image_alt_text: true,
+        }
+    }
+}
+
+/// Options for [`MarkdownDocument::to_html`](crate::MarkdownDocument::to_html).
+#[derive(Debug, Clone, Default)]
+pub struct HtmlOptions {
+    /// Wrap the content in a `<!DOCTYPE html>` page with a `<head>`. Default is false, for a
+    /// fragment to embed.
+    pub standalone: bool,
+    /// The options shared with the markdown, e.g. the excluded styles, heading detection and
+    /// image paths.
+    pub markdown: MarkdownOptions,
+}
+
+/// How images are written in markdown.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ImageSyntax {
+    /// `![alt](src)`
+    #[default]
+    Markdown,
+    /// `<img src="src" alt="alt" width="200" height="100">`, which keeps the displayed size
+    HtmlImg,
+}
+
+/// How the paragraphs of a table cell are joined.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CellParagraphJoin {
+    /// `first<br/>second`
+    #[default]
+    Br,
+    /// `first second`
+    Space,
+    /// A line break, e.g. for CSV-like exports. Note that this breaks markdown pipe tables.
+    Newline,
+    /// `<p>first</p><p>second</p>`, for cells with more than one paragraph
+    Paragraphs,
+}
+
+impl CellParagraphJoin {
+    /// Join the paragraphs of a cell, which are already converted to markdown.
+    pub fn join(&self, paragraphs: &[String]) -> String {
+        match self {
+            CellParagraphJoin::Br => paragraphs.join("<br/>"),
+            CellParagraphJoin::Space => paragraphs.join(" "),
+            CellParagraphJoin::Newline => paragraphs.join("\n"),
+            CellParagraphJoin::Paragraphs if paragraphs.len() > 1 => paragraphs
+                .iter()
+                .map(|paragraph| format!("<p>{paragraph}</p>"))
+                .collect(),
+            CellParagraphJoin::Paragraphs => paragraphs.concat(),
+        }
+    }
+}
+
+/// How the images of a document are written in JSON.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ImageJsonMode {
+    /// The image data as a data URL, e.g. `data:image/png;base64,...`
+    #[default]
+    Base64,
+    /// The path the markdown refers to, e.g. `./media/image1.png`, for images that are written to
+    /// disk separately
+    Paths,
+    /// Leave out the images
+    Omit,
+}
+
+/// How consecutive paragraphs are separated in markdown.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ParagraphSpacing {
+    /// A blank line, so that each paragraph is a markdown paragraph
+    #[default]
+    Loose,
+    /// A line break, for targets that render single line breaks, e.g. as in chat messages
+    Tight,
+}
+
+/// How font sizes are written in JSON.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FontSizeJson {
+    /// Half-points, as in the document, e.g. `19`
+    #[default]
+    HalfPoints,
+    /// Points, e.g. `9.5`, see [`BlockStyle::size_in_points`](crate::BlockStyle::size_in_points)
+    Points,
+    /// A named size, e.g. `"s"`, see [`BlockStyle::size_name`](crate::BlockStyle::size_name)
+    Named,
+}
+
+/// How text with a phonetic guide (ruby) is written.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RubySyntax {
+    /// `漢字(かんじ)`
+    #[default]
+    Parentheses,
+    /// `<ruby>漢字<rt>かんじ</rt></ruby>`
+    Html,
+}
+
+/// How nested list items are indented in markdown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListIndent {
+    /// This many spaces more than the parent item, but at least up to the text of the parent
+    /// item, so that the items below `1. ` are indented by three with two spaces
+    Spaces(usize),
+    /// Up to the text of the parent item, e.g. two spaces below `- ` and three below `1. `
+    MarkerWidth,
+    /// A tab per level
+    Tabs,
+}
+
+impl Default for ListIndent {
+    fn default() -> Self {
+        ListIndent::Spaces(4)
+    }
+}
+
+/// How tracked changes are converted.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TrackChanges {
+    /// Keep insertions and drop deletions, as if all changes were accepted
+    #[default]
+    AcceptAll,
+    /// Drop insertions and keep deletions, as if all changes were rejected
+    RejectAll,
+    /// Keep both, marked with CriticMarkup: `{++inserted++}` and `{--deleted--}`
+    ShowMarkup,
+    /// Keep both, marked with HTML and the author of the change:
+    /// `<ins data-author="Ada">inserted</ins>` and `<del data-author="Ada">deleted</del>`
+    HtmlMarkup,
+}
+
+/// How equations are rendered in markdown.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum MathOutput {
+    /// Inline equations as `$...$`, display equations as `$$...$$`
+    #[default]
+    Latex,
+    /// Replace every equation with a placeholder, e.g. `[equation]`
+    Placeholder(String),
+}
+
+/// The character used to mark emphasis in markdown.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EmphasisMarker {
+    #[default]
+    Asterisk,
+    Underscore,
+}
+
+impl EmphasisMarker {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmphasisMarker::Asterisk => "*",
+            EmphasisMarker::Underscore => "_",
+        }
+    }
+}
+
+/// A function that changes the markdown of a block, see
+/// [`MarkdownOptions::block_postprocess`].
+#[derive(Clone)]
+pub struct BlockPostprocess(Arc<BlockPostprocessFn>);
+
+/// The function of a [`BlockPostprocess`].
+type BlockPostprocessFn = dyn Fn(&MarkdownContent, String) -> String + Send + Sync;
+
+impl BlockPostprocess {
+    pub fn new(
+        postprocess: impl Fn(&MarkdownContent, String) -> String + Send + Sync + 'static,
+    ) -> Self {
+        BlockPostprocess(Arc::new(postprocess))
+    }
+
+    pub fn apply(&self, content: &MarkdownContent, markdown: String) -> String {
+        (self.0)(content, markdown)
+    }
+}
+
+impl fmt::Debug for BlockPostprocess {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "BlockPostprocess(..)")
+    }
+}
+
+/// How hyperlinks to local files are converted.
+#[derive(Clone, Default)]
+pub enum FileLinkHandling {
+    /// Keep the link as is
+    #[default]
+    Keep,
+    /// Replace the link by its text
+    Strip,
+    /// Replace the link target, e.g. to make it relative to an export folder
+    Rewrite(Arc<dyn Fn(&str) -> String + Send + Sync>),
+}
+
+impl fmt::Debug for FileLinkHandling {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileLinkHandling::Keep => write!(f, "Keep"),
+            FileLinkHandling::Strip => write!(f, "Strip"),
+            FileLinkHandling::Rewrite(_) => write!(f, "Rewrite(..)"),
+        }
+    }
+}
+
+/// How the paragraphs of a style are rendered, see [`MarkdownOptions::style_overrides`]. Fields
+/// that are `None` keep what the style itself says.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct StyleOverride {
+    /// The heading level, 1 for `#`, or 0 to render a heading style as a paragraph
+    pub heading_level: Option<usize>,
+    /// Render the paragraphs as a block quote or code block
+    pub block: Option<StyleBlock>,
+    /// Text to put at the start of the paragraph, e.g. `"- "` or `"**Note:** "`
+    pub marker: Option<String>,
+}
+
+/// A block that the paragraphs of a style are rendered as, see [`StyleOverride::block`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StyleBlock {
+    /// `> text`
+    Quote,
+    /// A fenced code block with the text of the paragraph, without markup
+    Code,
+}
@@ -0,0 +1,576 @@
+//! Raw access to the parts of the docx package, for content that docx-rust doesn't model.
+
+use roxmltree::Node;
+use std::collections::{HashMap, HashSet};
+use std::io::{Cursor, Read, Write};
+use zip::result::ZipResult;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+pub const W_NS: &str = "http://schemas.openxmlformats.org/wordprocessingml/2006/main";
+pub const M_NS: &str = "http://schemas.openxmlformats.org/officeDocument/2006/math";
+pub const R_NS: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+pub const A_NS: &str = "http://schemas.openxmlformats.org/drawingml/2006/main";
+pub const DC_NS: &str = "http://purl.org/dc/elements/1.1/";
+pub const V_NS: &str = "urn:schemas-microsoft-com:vml";
+pub const O_NS: &str = "urn:schemas-microsoft-com:office:office";
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+
+/// All parts of the docx (zip) package, keyed by their path, e.g. `word/document.xml`.
+pub struct Package {
+    parts: HashMap<String, Vec<u8>>,
+}
+
+impl Package {
+    pub fn from_bytes(bytes: &[u8]) -> ZipResult<Self> {
+        let mut archive = ZipArchive::new(Cursor::new(bytes))?;
+        let mut parts = HashMap::new();
+        for i in 0..archive.len() {
+            let mut file = archive.by_index(i)?;
+            if file.is_dir() {
+                continue;
+            }
+            let mut data = vec![];
+            file.read_to_end(&mut data)?;
+            parts.insert(file.name().to_string(), data);
+        }
+        Ok(Package { parts })
+    }
+
+    /// The XML parts, including relationship parts.
+    fn xml_parts(&self) -> impl Iterator<Item = (&String, &Vec<u8>)> {
+        self.parts
+            .iter()
+            .filter(|(name, _)| name.ends_with(".xml") || name.ends_with(".rels"))
+    }
+
+    /// The first XML part that isn't UTF-8, e.g. because it is UTF-16. Neither parser supports
+    /// such parts.
+    pub fn non_utf8_part(&self) -> Option<&str> {
+        self.xml_parts()
+            .find(|(_, data)| {
+                data.starts_with(&[0xFF, 0xFE])
+                    || data.starts_with(&[0xFE, 0xFF])
+                    || std::str::from_utf8(data).is_err()
+            })
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Remove a leading UTF-8 byte order mark from the XML parts, which some generators write and
+    /// the XML parsers don't expect. Returns whether any was removed.
+    pub fn strip_byte_order_marks(&mut self) -> bool {
+        let mut stripped = false;
+        for (name, data) in self.parts.iter_mut() {
+            if (name.ends_with(".xml") || name.ends_with(".rels")) && data.starts_with(UTF8_BOM) {
+                data.drain(..UTF8_BOM.len());
+                stripped = true;
+            }
+        }
+        stripped
+    }
+
+    /// Write the package as a zip file again, e.g. after removing byte order marks.
+    pub fn to_bytes(&self) -> ZipResult<Vec<u8>> {
+        let mut writer = ZipWriter::new(Cursor::new(vec![]));
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        for (name, data) in &self.parts {
+            writer.start_file(name.as_str(), options)?;
+            writer.write_all(data)?;
+        }
+        Ok(writer.finish()?.into_inner())
+    }
+
+    /// The media parts, e.g. images, keyed by their path relative to `word/`, e.g.
+    /// `media/image1.png`, like the targets of the relationships to them.
+    pub fn media(&self) -> impl Iterator<Item = (&str, &[u8])> {
+        self.parts.iter().filter_map(|(name, data)| {
+            let name = name
+                .strip_prefix("word/")
+                .filter(|name| name.starts_with("media/"))?;
+            Some((name, data.as_slice()))
+        })
+    }
+
+    /// The raw content of a part.
+    pub fn bytes(&self, name: &str) -> Option<&[u8]> {
+        self.parts.get(name).map(|data| data.as_slice())
+    }
+
+    /// The content of an XML part as text.
+    pub fn xml(&self, name: &str) -> Option<&str> {
+        self.parts
+            .get(name)
+            .and_then(|data| std::str::from_utf8(data).ok())
+    }
+
+    /// An XML part, parsed, so that it is parsed once for everything that is read from it.
+    pub fn parse(&self, name: &str) -> Option<roxmltree::Document<'_>> {
+        roxmltree::Document::parse(self.xml(name)?).ok()
+    }
+}
+
+/// The content type of a part, from an override or the default for its extension in the root
+/// element of `[Content_Types].xml`.
+pub fn content_type(types: Option<Node>, name: &str) -> Option<String> {
+    let types = types?;
+    let part_name = format!("/{}", name.trim_start_matches('/'));
+    let extension = name.rsplit_once('.').map(|(_, extension)| extension);
+    let overridden = types.children().find(|node| {
+        node.has_tag_name("Override") && node.attribute("PartName") == Some(part_name.as_str())
+    });
+    let default = || {
+        types.children().find(|node| {
+            node.has_tag_name("Default")
+                && extension.is_some_and(|extension| {
+                    node.attribute("Extension")
+                        .is_some_and(|default| default.eq_ignore_ascii_case(extension))
+                })
+        })
+    };
+    overridden
+        .or_else(default)
+        .and_then(|node| node.attribute("ContentType"))
+        .map(|content_type| content_type.to_string())
+}
+
+/// The targets of the relationships in a relationships part, such as
+/// `word/_rels/document.xml.rels`, keyed by relationship id. Unlike the relationships of
+/// docx-rust, this includes charts, diagrams and alternative format chunks.
+pub fn targets(relationships: Option<Node>) -> HashMap<String, String> {
+    let Some(relationships) = relationships else {
+        return HashMap::new();
+    };
+    relationships
+        .children()
+        .filter(|node| node.has_tag_name("Relationship"))
+        .filter_map(|node| {
+            let id = node.attribute("Id")?;
+            let target = node.attribute("Target")?;
+            Some((id.to_string(), target.to_string()))
+        })
+        .collect()
+}
+
+/// The target modes (e.g. `External`) of the relationships in a relationships part, such as
+/// `word/_rels/document.xml.rels`, keyed by relationship id.
+pub fn target_modes(relationships: Option<Node>) -> HashMap<String, String> {
+    let Some(relationships) = relationships else {
+        return HashMap::new();
+    };
+    relationships
+        .children()
+        .filter(|node| node.has_tag_name("Relationship"))
+        .filter_map(|node| {
+            let id = node.attribute("Id")?;
+            let mode = node.attribute("TargetMode")?;
+            Some((id.to_string(), mode.to_string()))
+        })
+        .collect()
+}
+
+/// The targets of the external hyperlink relationships in a relationships part, such as
+/// `word/_rels/document.xml.rels`, in the order of the part.
+pub fn hyperlink_targets(relationships: Option<Node>) -> Vec<String> {
+    let Some(relationships) = relationships else {
+        return vec![];
+    };
+    relationships
+        .children()
+        .filter(|node| node.has_tag_name("Relationship"))
+        .filter(|node| {
+            node.attribute("Type")
+                .is_some_and(|ty| ty.ends_with("/hyperlink"))
+        })
+        .filter(|node| node.attribute("TargetMode") == Some("External"))
+        .filter_map(|node| Some(node.attribute("Target")?.to_string()))
+        .collect()
+}
+
+/// The package path of a relationship target of the main document part, e.g. `charts/chart1.xml`
+/// becomes `word/charts/chart1.xml`.
+pub fn part_path(target: &str) -> String {
+    match target.strip_prefix('/') {
+        Some(absolute) => absolute.to_string(),
+        None => format!("word/{}", target),
+    }
+}
+
+/// The people named in a Dublin Core element of the core properties (`docProps/core.xml`), e.g.
+/// `dc:creator`. The element may be repeated, and may list several names separated by `;`.
+pub fn core_names(core: Option<Node>, name: &str) -> Vec<String> {
+    let Some(core) = core else {
+        return vec![];
+    };
+    core.children()
+        .filter(|node| is_element(node, DC_NS, name))
+        .filter_map(|node| node.text())
+        .flat_map(|names| names.split(';'))
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// The font family set by run properties (`w:rPr`), from their `w:rFonts`. Theme fonts, such as
+/// `w:asciiTheme`, are not resolved.
+pub fn run_font(properties: Option<Node>) -> Option<String> {
+    let fonts = w_children(properties, "rFonts").into_iter().next()?;
+    ["ascii", "hAnsi", "eastAsia", "cs"]
+        .into_iter()
+        .find_map(|attribute| fonts.attribute((W_NS, attribute)))
+        .map(|font| font.to_string())
+}
+
+/// The shading color of run properties (`w:rPr`), e.g. `D9D9D9`, from their `w:shd`.
+pub fn run_shading(properties: Option<Node>) -> Option<String> {
+    w_children(properties, "shd")
+        .into_iter()
+        .find_map(|shading| shading.attribute((W_NS, "fill")))
+        .filter(|fill| !fill.eq_ignore_ascii_case("auto"))
+        .map(|fill| fill.to_string())
+}
+
+/// The font color of run properties (`w:rPr`), e.g. `FF0000`, from their `w:color`.
+pub fn run_color(properties: Option<Node>) -> Option<String> {
+    w_children(properties, "color")
+        .into_iter()
+        .find_map(|color| color.attribute((W_NS, "val")))
+        .filter(|color| !color.eq_ignore_ascii_case("auto"))
+        .map(|color| color.to_string())
+}
+
+/// The highlight of run properties (`w:rPr`), e.g. `yellow`, from their `w:highlight`.
+pub fn run_highlight(properties: Option<Node>) -> Option<String> {
+    w_children(properties, "highlight")
+        .into_iter()
+        .find_map(|highlight| highlight.attribute((W_NS, "val")))
+        .filter(|highlight| *highlight != "none")
+        .map(|highlight| highlight.to_string())
+}
+
+/// The justification of a paragraph (`w:p`), e.g. `center`, from the `w:jc` of its properties.
+pub fn justification(paragraph: Option<Node>) -> Option<String> {
+    w_children(paragraph, "pPr")
+        .into_iter()
+        .flat_map(|properties| w_children(Some(properties), "jc"))
+        .find_map(|jc| jc.attribute((W_NS, "val")))
+        .map(|jc| jc.to_string())
+}
+
+/// The vertical alignment of run properties (`w:rPr`), e.g. `superscript`, from their
+/// `w:vertAlign`.
+pub fn vertical_align(properties: Option<Node>) -> Option<String> {
+    w_children(properties, "vertAlign")
+        .into_iter()
+        .find_map(|align| align.attribute((W_NS, "val")))
+        .map(|align| align.to_string())
+}
+
+/// The default font family of the document, from the `w:docDefaults` of `word/styles.xml`.
+pub fn default_font(styles: Option<Node>) -> Option<String> {
+    let defaults = w_children(styles, "docDefaults");
+    let properties = w_children(defaults.first().copied(), "rPrDefault");
+    run_font(
+        w_children(properties.first().copied(), "rPr")
+            .first()
+            .copied(),
+    )
+}
+
+/// The fonts of the numbering levels in `word/numbering.xml`, keyed by numbering id and level,
+/// e.g. `Wingdings` for a level with a symbol bullet.
+pub fn numbering_fonts(numbering: Option<Node>) -> HashMap<(isize, usize), String> {
+    let Some(numbering) = numbering else {
+        return HashMap::new();
+    };
+    numbering_levels(numbering)
+        .into_iter()
+        .filter_map(|(id, _, ilvl, level)| {
+            let font = run_font(w_children(Some(level), "rPr").first().copied())?;
+            Some(((id, ilvl), font))
+        })
+        .collect()
+}
+
+/// The start values of the numbering levels in `word/numbering.xml`, keyed by numbering id and
+/// level. The `w:startOverride` of a numbering takes precedence over the `w:start` of its
+/// abstract numbering, so that a list that reuses an abstract numbering can restart.
+pub fn numbering_starts(numbering: Option<Node>) -> HashMap<(isize, usize), usize> {
+    let Some(numbering) = numbering else {
+        return HashMap::new();
+    };
+    let value =
+        |node: Option<Node>| -> Option<usize> { node?.attribute((W_NS, "val"))?.parse().ok() };
+    numbering_levels(numbering)
+        .into_iter()
+        .filter_map(|(id, num, ilvl, level)| {
+            let start_override = w_children(Some(num), "lvlOverride")
+                .into_iter()
+                .filter(|level_override| {
+                    level_override.attribute((W_NS, "ilvl")) == Some(ilvl.to_string().as_str())
+                })
+                .find_map(|level_override| {
+                    value(
+                        w_children(Some(level_override), "startOverride")
+                            .first()
+                            .copied(),
+                    )
+                });
+            let start = start_override
+                .or_else(|| value(w_children(Some(level), "start").first().copied()))?;
+            Some(((id, ilvl), start))
+        })
+        .collect()
+}
+
+/// The levels (`w:lvl`) of the abstract numbering of each numbering (`w:num`), with the numbering
+/// id and the level index.
+fn numbering_levels<'a, 'input>(
+    root: Node<'a, 'input>,
+) -> Vec<(isize, Node<'a, 'input>, usize, Node<'a, 'input>)> {
+    let abstract_numberings: HashMap<&str, Node> = w_children(Some(root), "abstractNum")
+        .into_iter()
+        .filter_map(|node| Some((node.attribute((W_NS, "abstractNumId"))?, node)))
+        .collect();
+    let mut levels = vec![];
+    for num in w_children(Some(root), "num") {
+        let Some(id) = num
+            .attribute((W_NS, "numId"))
+            .and_then(|id| id.parse::<isize>().ok())
+        else {
+            continue;
+        };
+        let Some(abstract_numbering) = w_children(Some(num), "abstractNumId")
+            .first()
+            .and_then(|node| node.attribute((W_NS, "val")))
+            .and_then(|abstract_id| abstract_numberings.get(abstract_id))
+        else {
+            continue;
+        };
+        for level in w_children(Some(*abstract_numbering), "lvl") {
+            if let Some(ilvl) = level
+                .attribute((W_NS, "ilvl"))
+                .and_then(|ilvl| ilvl.parse::<usize>().ok())
+            {
+                levels.push((id, num, ilvl, level));
+            }
+        }
+    }
+    levels
+}
+
+/// The default space after paragraphs, in twentieths of a point, from the `w:docDefaults` of
+/// `word/styles.xml`.
+pub fn default_space_after(styles: Option<Node>) -> Option<usize> {
+    let defaults = w_children(styles, "docDefaults");
+    let properties = w_children(defaults.first().copied(), "pPrDefault");
+    let paragraph_properties = w_children(properties.first().copied(), "pPr");
+    w_children(paragraph_properties.first().copied(), "spacing")
+        .first()?
+        .attribute((W_NS, "after"))?
+        .parse()
+        .ok()
+}
+
+/// The ids of the paragraph styles in `word/styles.xml` that set `w:contextualSpacing`, i.e.
+/// that leave out the space between paragraphs of the same style.
+pub fn contextual_spacing_styles(styles: Option<Node>) -> HashSet<String> {
+    w_children(styles, "style")
+        .into_iter()
+        .filter(|style| {
+            is_on(
+                w_children(Some(*style), "pPr").first().copied(),
+                "contextualSpacing",
+            )
+        })
+        .filter_map(|style| Some(style.attribute((W_NS, "styleId"))?.to_string()))
+        .collect()
+}
+
+/// Whether a run comes after a page or column break without text in between, e.g. the run of
+/// `bar` in `<w:r><w:t>foo</w:t><w:br w:type="page"/></w:r><w:r><w:t>bar</w:t></w:r>`.
+pub fn follows_break(run: Node) -> bool {
+    for sibling in run.prev_siblings().skip(1).filter(Node::is_element) {
+        if is_element(&sibling, W_NS, "r") {
+            return sibling
+                .children()
+                .rfind(Node::is_element)
+                .is_some_and(|last| {
+                    is_element(&last, W_NS, "br")
+                        && matches!(last.attribute((W_NS, "type")), Some("page" | "column"))
+                });
+        }
+        if sibling
+            .descendants()
+            .any(|node| is_element(&node, W_NS, "t"))
+        {
+            return false;
+        }
+    }
+    false
+}
+
+/// Whether the on/off property `name` is switched on in paragraph or run properties, e.g.
+/// `<w:contextualSpacing/>` in a `w:pPr`.
+pub fn is_on(properties: Option<Node>, name: &str) -> bool {
+    w_children(properties, name)
+        .first()
+        .is_some_and(|node| !matches!(node.attribute((W_NS, "val")), Some("0" | "false")))
+}
+
+/// Whether the on/off property `name` is there but switched off, e.g. `<w:b w:val="0"/>`.
+pub fn is_off(properties: Option<Node>, name: &str) -> bool {
+    w_children(properties, name)
+        .first()
+        .is_some_and(|node| matches!(node.attribute((W_NS, "val")), Some("0" | "false")))
+}
+
+/// Check whether a node is the element `name` in the `ns` namespace.
+pub fn is_element(node: &Node, ns: &str, name: &str) -> bool {
+    node.is_element() && node.tag_name().name() == name && node.tag_name().namespace() == Some(ns)
+}
+
+/// The XML source of an element, including its own tags.
+pub fn outer_xml<'input>(node: Node<'_, 'input>) -> &'input str {
+    &node.document().input_text()[node.range()]
+}
+
+/// The text of all `w:t` elements below `node`.
+pub fn text_content(node: Node) -> String {
+    node.descendants()
+        .filter(|descendant| is_element(descendant, W_NS, "t"))
+        .filter_map(|text| text.text())
+        .collect()
+}
+
+/// The child elements of `node` named `name` in the wordprocessingml namespace.
+pub fn w_children<'a, 'input>(node: Option<Node<'a, 'input>>, name: &str) -> Vec<Node<'a, 'input>> {
+    match node {
+        Some(node) => node
+            .children()
+            .filter(|child| is_element(child, W_NS, name))
+            .collect(),
+        None => vec![],
+    }
+}
+
+/// The vertical merge of a table cell (`w:tc`), `restart` or `continue`, from its `w:vMerge`.
+pub fn vertical_merge(cell: Option<Node>) -> Option<String> {
+    w_children(cell, "tcPr")
+        .into_iter()
+        .flat_map(|properties| w_children(Some(properties), "vMerge"))
+        .map(|merge| {
+            merge
+                .attribute((W_NS, "val"))
+                .unwrap_or("continue")
+                .to_string()
+        })
+        .next()
+}
+
+/// The number of grid columns that a table cell (`w:tc`) spans, from its `w:gridSpan`.
+pub fn grid_span(cell: Option<Node>) -> Option<usize> {
+    w_children(cell, "tcPr")
+        .into_iter()
+        .flat_map(|properties| w_children(Some(properties), "gridSpan"))
+        .find_map(|span| span.attribute((W_NS, "val"))?.parse().ok())
+}
+
+/// The colors of the color scheme of the document theme (`word/theme/theme1.xml`), keyed by
+/// their name, e.g. `accent1` => `4F81BD`.
+pub fn theme_colors(theme: Option<Node>) -> HashMap<String, String> {
+    let Some(theme) = theme else {
+        return HashMap::new();
+    };
+    let Some(scheme) = theme
+        .descendants()
+        .find(|node| is_element(node, A_NS, "clrScheme"))
+    else {
+        return HashMap::new();
+    };
+    scheme
+        .children()
+        .filter(|node| node.is_element())
+        .filter_map(|color| {
+            let value = color.children().find(|node| node.is_element())?;
+            let rgb = match value.tag_name().name() {
+                "sysClr" => value.attribute("lastClr")?,
+                _ => value.attribute("val")?,
+            };
+            Some((color.tag_name().name().to_string(), rgb.to_string()))
+        })
+        .collect()
+}
+
+/// The background color of a table cell (`w:tc`), e.g. `FF0000`, from its shading. Theme colors
+/// are looked up in `theme_colors`, without applying their tint or shade.
+pub fn cell_background(
+    cell: Option<Node>,
+    theme_colors: &HashMap<String, String>,
+) -> Option<String> {
+    let properties = w_children(cell, "tcPr");
+    let shading = w_children(properties.first().copied(), "shd")
+        .into_iter()
+        .next()?;
+    if let Some(fill) = shading
+        .attribute((W_NS, "fill"))
+        .filter(|fill| !fill.eq_ignore_ascii_case("auto"))
+    {
+        return Some(fill.to_uppercase());
+    }
+    let theme_color = match shading.attribute((W_NS, "themeFill"))? {
+        "dark1" | "text1" => "dk1",
+        "light1" | "background1" => "lt1",
+        "dark2" | "text2" => "dk2",
+        "light2" | "background2" => "lt2",
+        "hyperlink" => "hlink",
+        "followedHyperlink" => "folHlink",
+        accent => accent,
+    };
+    theme_colors
+        .get(theme_color)
+        .map(|color| color.to_uppercase())
+}
+
+/// The `w:body` element of the main document part.
+pub fn body<'a, 'input>(document: &'a roxmltree::Document<'input>) -> Option<Node<'a, 'input>> {
+    document
+        .root_element()
+        .children()
+        .find(|node| is_element(node, W_NS, "body"))
+}
+
+/// The `w:altChunk` elements of the body, with the number of `BodyContent` items before them.
+pub fn alt_chunks<'a, 'input>(body: Option<Node<'a, 'input>>) -> Vec<(usize, Node<'a, 'input>)> {
+    let mut chunks = vec![];
+    let mut position = 0;
+    for child in body.iter().flat_map(|body| body.children()) {
+        if is_element(&child, W_NS, "altChunk") {
+            chunks.push((position, child));
+        } else if BODY_CONTENT
+            .iter()
+            .any(|name| is_element(&child, W_NS, name))
+        {
+            position += 1;
+        }
+    }
+    chunks
+}
+
+/// Elements that docx-rust parses into `BodyContent`, so the raw body can be walked in lockstep.
+const BODY_CONTENT: [&str; 6] = ["p", "tbl", "sdt", "sectPr", "tc", "r"];
+
+/// The children of `w:body` that correspond, in order, to docx-rust's `BodyContent` items.
+pub fn body_content<'a, 'input>(body: Option<Node<'a, 'input>>) -> Vec<Node<'a, 'input>> {
+    match body {
+        Some(body) => body
+            .children()
+            .filter(|child| {
+                BODY_CONTENT
+                    .iter()
+                    .any(|name| is_element(child, W_NS, name))
+            })
+            .collect(),
+        None => vec![],
+    }
+}
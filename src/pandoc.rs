@@ -0,0 +1,298 @@
+//! Conversion to the JSON representation of pandoc's AST, as read by `pandoc -f json` and filters.
+
+use crate::utils::{link_target, link_text};
+use crate::{
+    MarkdownContent, MarkdownDocument, MarkdownOptions, MarkdownParagraph, MarkdownTable,
+    MarkdownTableRow, ParagraphStyle, SmartArtNode, TextType,
+};
+use serde_json::{json, Value};
+
+/// The version of pandoc-types the AST conforms to.
+const PANDOC_API_VERSION: [u32; 3] = [1, 23, 1];
+
+/// A list item that still has to be grouped into a (nested) list.
+struct ListItem {
+    level: usize,
+    /// The `ListAttributes` of an ordered list, or `None` for a bullet list.
+    attributes: Option<Value>,
+    blocks: Vec<Value>,
+}
+
+impl MarkdownDocument {
+    /// Convert the document to pandoc's JSON AST.
+    pub fn to_pandoc_json(&self) -> String {
+        let mut meta = serde_json::Map::new();
+        if let Some(title) = &self.title {
+            meta.insert(
+                "title".to_string(),
+                json!({ "t": "MetaInlines", "c": text_inlines(title) }),
+            );
+        }
+
+        let mut blocks = vec![];
+        let mut items: Vec<ListItem> = vec![];
+        for content in &self.content {
+            let item = match content {
+                MarkdownContent::Paragraph(paragraph) => self.list_item(paragraph),
+                _ => None,
+            };
+            match item {
+                Some(item) => {
+                    items.push(item);
+                    continue;
+                }
+                None if !items.is_empty() => blocks.extend(lists(&mut items.drain(..))),
+                None => (),
+            }
+            match content {
+                MarkdownContent::Paragraph(paragraph) => {
+                    let style = paragraph.resolved_style(&self.styles);
+                    let inlines = paragraph_inlines(self, paragraph, &style);
+                    match self.paragraph_heading_level(paragraph, &MarkdownOptions::default()) {
+                        Some(level) => blocks.push(json!({
+                            "t": "Header",
+                            "c": [level.clamp(0, 5) + 1, ["", [], []], inlines],
+                        })),
+                        None => blocks.push(json!({ "t": "Para", "c": inlines })),
+                    }
+                }
+                MarkdownContent::Table(table) => blocks.push(self.table(table)),
+                MarkdownContent::Chart(chart) => {
+                    let name = chart.title.as_ref().unwrap_or(&chart.chart_type);
+                    blocks.push(json!({ "t": "Para", "c": [
+                        { "t": "Emph", "c": text_inlines(&format!("Chart: {name}")) }
+                    ] }));
+                    blocks.push(self.table(&chart.table));
+                }
+                MarkdownContent::SmartArt(smart_art) => {
+                    blocks.push(smart_art_list(&smart_art.nodes))
+                }
+            }
+        }
+        blocks.extend(lists(&mut items.drain(..)));
+
+        json!({
+            "pandoc-api-version": PANDOC_API_VERSION,
+            "meta": meta,
+            "blocks": blocks,
+        })
+        .to_string()
+    }
+
+    fn list_item(&self, paragraph: &MarkdownParagraph) -> Option<ListItem> {
+        let style = paragraph.resolved_style(&self.styles);
+        let numbering = style.numbering.as_ref()?;
+        let id = numbering.id?;
+        let level = numbering.indent_level.unwrap_or(0).max(0) as usize;
+        let numbering = self.numberings.get(&id);
+        let numbering_level = numbering.and_then(|numbering| numbering.levels.get(level));
+        let format = numbering_level
+            .and_then(|numbering_level| numbering_level.format.as_deref())
+            .or_else(|| numbering?.format.as_deref());
+        let attributes = match format {
+            Some("bullet") => None,
+            _ => {
+                let start = numbering_level
+                    .and_then(|numbering_level| numbering_level.start)
+                    .unwrap_or(1);
+                let style = match format {
+                    Some("decimal" | "decimalZero") => "Decimal",
+                    Some("lowerLetter") => "LowerAlpha",
+                    Some("upperLetter") => "UpperAlpha",
+                    Some("lowerRoman") => "LowerRoman",
+                    Some("upperRoman") => "UpperRoman",
+                    _ => "DefaultStyle",
+                };
+                let level_text = numbering_level
+                    .and_then(|numbering_level| numbering_level.level_text.as_deref())
+                    .unwrap_or_default();
+                let delimiter = match level_text {
+                    text if text.starts_with('(') && text.ends_with(')') => "TwoParens",
+                    text if text.ends_with(')') => "OneParen",
+                    text if text.ends_with('.') => "Period",
+                    _ => "DefaultDelim",
+                };
+                Some(json!([start, { "t": style }, { "t": delimiter }]))
+            }
+        };
+        Some(ListItem {
+            level,
+            attributes,
+            blocks: vec![json!({ "t": "Plain", "c": paragraph_inlines(self, paragraph, &style) })],
+        })
+    }
+
+    fn table(&self, table: &MarkdownTable) -> Value {
+        let columns = table.iter().map(|row| row.columns()).max().unwrap_or(0);
+        let row = |row: &MarkdownTableRow| {
+            let mut cells: Vec<Value> = row
+                .cells
+                .iter()
+                .map(|cell| {
+                    let blocks: Vec<Value> = cell
+                        .paragraphs
+                        .iter()
+                        .map(|paragraph| {
+                            let style = paragraph.resolved_style(&self.styles);
+                            json!({ "t": "Plain", "c": paragraph_inlines(self, paragraph, &style) })
+                        })
+                        .collect();
+                    json!([["", [], []], { "t": "AlignDefault" }, 1, cell.span(), blocks])
+                })
+                .collect();
+            // Rows with fewer columns are padded with empty cells.
+            cells.extend(
+                (row.columns()..columns)
+                    .map(|_| json!([["", [], []], { "t": "AlignDefault" }, 1, 1, []])),
+            );
+            json!([["", [], []], cells])
+        };
+        let (head, body): (Vec<_>, Vec<_>) = table.iter().partition(|row| row.is_header);
+        let head: Vec<Value> = head.into_iter().map(row).collect();
+        let body: Vec<Value> = body.into_iter().map(row).collect();
+        let col_specs: Vec<Value> = (0..columns)
+            .map(|_| json!([{ "t": "AlignDefault" }, { "t": "ColWidthDefault" }]))
+            .collect();
+        json!({
+            "t": "Table",
+            "c": [
+                ["", [], []],
+                [null, []],
+                col_specs,
+                [["", [], []], head],
+                [[["", [], []], 0, [], body]],
+                [["", [], []], []],
+            ],
+        })
+    }
+}
+
+/// Group consecutive list items into bullet and ordered lists, nesting deeper levels into the
+/// preceding item.
+fn lists(items: &mut dyn Iterator<Item = ListItem>) -> Vec<Value> {
+    let mut items = items.peekable();
+    let mut blocks = vec![];
+    let mut current: Option<(Option<Value>, Vec<Vec<Value>>)> = None;
+    while let Some(item) = items.next() {
+        let mut item_blocks = item.blocks;
+        let mut nested = vec![];
+        while let Some(next) = items.peek() {
+            if next.level <= item.level {
+                break;
+            }
+            nested.push(items.next().unwrap());
+        }
+        if !nested.is_empty() {
+            item_blocks.extend(lists(&mut nested.into_iter()));
+        }
+        match &mut current {
+            Some((attributes, list)) if *attributes == item.attributes => list.push(item_blocks),
+            _ => {
+                if let Some(list) = current.take() {
+                    blocks.push(list_block(list));
+                }
+                current = Some((item.attributes, vec![item_blocks]));
+            }
+        }
+    }
+    if let Some(list) = current {
+        blocks.push(list_block(list));
+    }
+    blocks
+}
+
+fn list_block((attributes, items): (Option<Value>, Vec<Vec<Value>>)) -> Value {
+    match attributes {
+        Some(attributes) => json!({ "t": "OrderedList", "c": [attributes, items] }),
+        None => json!({ "t": "BulletList", "c": items }),
+    }
+}
+
+/// The nodes of a SmartArt diagram as a nested bullet list.
+fn smart_art_list(nodes: &[SmartArtNode]) -> Value {
+    let items: Vec<Vec<Value>> = nodes
+        .iter()
+        .map(|node| {
+            let mut blocks = vec![json!({ "t": "Plain", "c": text_inlines(&node.text) })];
+            if !node.children.is_empty() {
+                blocks.push(smart_art_list(&node.children));
+            }
+            blocks
+        })
+        .collect();
+    list_block((None, items))
+}
+
+fn paragraph_inlines(
+    doc: &MarkdownDocument,
+    paragraph: &MarkdownParagraph,
+    style: &ParagraphStyle,
+) -> Vec<Value> {
+    let mut inlines = vec![];
+    for block in &paragraph.blocks {
+        let inline = match block.text_type {
+            TextType::Image => vec![json!({ "t": "Image", "c": [
+                ["", [], []],
+                text_inlines(link_text(&block.text)),
+                [link_target(&block.text), ""],
+            ] })],
+            TextType::Link => vec![json!({ "t": "Link", "c": [
+                ["", [], []],
+                text_inlines(link_text(&block.text)),
+                [link_target(&block.text), ""],
+            ] })],
+            TextType::Math => {
+                vec![json!({ "t": "Math", "c": [{ "t": "InlineMath" }, block.text] })]
+            }
+            TextType::MathBlock => {
+                vec![json!({ "t": "Math", "c": [{ "t": "DisplayMath" }, block.text] })]
+            }
+            TextType::NoteReference => {
+                let blocks: Vec<Value> = doc
+                    .notes
+                    .iter()
+                    .filter(|note| note.label == block.text)
+                    .flat_map(|note| &note.paragraphs)
+                    .map(|note_paragraph| {
+                        let style = note_paragraph.resolved_style(&doc.styles);
+                        json!({ "t": "Para", "c": paragraph_inlines(doc, note_paragraph, &style) })
+                    })
+                    .collect();
+                vec![json!({ "t": "Note", "c": blocks })]
+            }
+            TextType::BookmarkLink | TextType::CommentReference => vec![],
+            TextType::ClearBreak | TextType::LineBreak => vec![json!({ "t": "LineBreak" })],
+            _ => {
+                let block_style = block.resolved_style(style);
+                let mut inline = text_inlines(&block.text);
+                for (enabled, name) in [
+                    (block_style.strike, "Strikeout"),
+                    (block_style.underline, "Underline"),
+                    (block_style.italics, "Emph"),
+                    (block_style.bold, "Strong"),
+                ] {
+                    if enabled {
+                        inline = vec![json!({ "t": name, "c": inline })];
+                    }
+                }
+                inline
+            }
+        };
+        inlines.extend(inline);
+    }
+    inlines
+}
+
+/// Split text into pandoc `Str` and `Space` inlines.
+fn text_inlines(text: &str) -> Vec<Value> {
+    let mut inlines = vec![];
+    for (i, word) in text.split(' ').enumerate() {
+        if i > 0 {
+            inlines.push(json!({ "t": "Space" }));
+        }
+        if !word.is_empty() {
+            inlines.push(json!({ "t": "Str", "c": word }));
+        }
+    }
+    inlines
+}
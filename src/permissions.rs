@@ -0,0 +1,54 @@
+//! Editable ranges of a protected document (`w:permStart` / `w:permEnd`).
+
+use crate::package::{is_element, W_NS};
+use roxmltree::Node;
+use serde::Serialize;
+
+/// A range of the document that may be edited, even though the document is protected.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionRange {
+    pub id: String,
+    /// The user that may edit the range
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub editor: Option<String>,
+    /// The group that may edit the range, e.g. `everyone`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub editor_group: Option<String>,
+    /// The text in the range, with a newline between paragraphs
+    pub text: String,
+}
+
+/// Collect the permission ranges of the document body.
+pub fn permission_ranges(body: Node) -> Vec<PermissionRange> {
+    let mut ranges: Vec<PermissionRange> = vec![];
+    // Indices in `ranges` of the ranges that haven't ended yet.
+    let mut open: Vec<usize> = vec![];
+    for node in body.descendants() {
+        if is_element(&node, W_NS, "permStart") {
+            if let Some(id) = node.attribute((W_NS, "id")) {
+                open.push(ranges.len());
+                ranges.push(PermissionRange {
+                    id: id.to_string(),
+                    editor: node.attribute((W_NS, "ed")).map(|ed| ed.to_string()),
+                    editor_group: node.attribute((W_NS, "edGrp")).map(|grp| grp.to_string()),
+                    text: "".to_string(),
+                });
+            }
+        } else if is_element(&node, W_NS, "permEnd") {
+            let id = node.attribute((W_NS, "id"));
+            open.retain(|i| Some(ranges[*i].id.as_str()) != id);
+        } else if is_element(&node, W_NS, "t") {
+            for i in &open {
+                ranges[*i].text.push_str(node.text().unwrap_or_default());
+            }
+        } else if is_element(&node, W_NS, "p") {
+            for i in &open {
+                if !ranges[*i].text.is_empty() {
+                    ranges[*i].text.push('\n');
+                }
+            }
+        }
+    }
+    ranges
+}
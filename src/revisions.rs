@@ -0,0 +1,78 @@
+//! Tracked changes (`w:ins` / `w:del`) and comments with their author and date, e.g. for an audit
+//! timeline.
+
+use crate::comments::comment_text;
+use crate::package::{is_element, Package, W_NS};
+use roxmltree::Node;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RevisionKind {
+    Insertion,
+    Deletion,
+    Comment,
+}
+
+/// A tracked change or a comment.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevisionEvent {
+    pub kind: RevisionKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// The date as written in the document, e.g. `2014-06-25T10:40:00Z`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    /// The inserted or deleted text, or the text of the comment
+    pub text: String,
+}
+
+/// Collect the insertions and deletions of the document body, in document order. Changes
+/// without text, such as a changed paragraph mark, are left out.
+pub fn revisions(body: Node) -> Vec<RevisionEvent> {
+    body.descendants()
+        .filter_map(|node| {
+            let (kind, text_element) = if is_element(&node, W_NS, "ins") {
+                (RevisionKind::Insertion, "t")
+            } else if is_element(&node, W_NS, "del") {
+                (RevisionKind::Deletion, "delText")
+            } else {
+                return None;
+            };
+            let text: String = node
+                .descendants()
+                .filter(|descendant| is_element(descendant, W_NS, text_element))
+                .filter_map(|descendant| descendant.text())
+                .collect();
+            (!text.is_empty()).then(|| event(node, kind, text))
+        })
+        .collect()
+}
+
+/// Collect the comments of `word/comments.xml`.
+pub fn comments(package: &Package) -> Vec<RevisionEvent> {
+    let Some(comments) = package
+        .xml("word/comments.xml")
+        .and_then(|xml| roxmltree::Document::parse(xml).ok())
+    else {
+        return vec![];
+    };
+    comments
+        .root_element()
+        .children()
+        .filter(|node| is_element(node, W_NS, "comment"))
+        .map(|comment| event(comment, RevisionKind::Comment, comment_text(comment)))
+        .collect()
+}
+
+fn event(node: Node, kind: RevisionKind, text: String) -> RevisionEvent {
+    RevisionEvent {
+        kind,
+        author: node
+            .attribute((W_NS, "author"))
+            .map(|author| author.to_string()),
+        date: node.attribute((W_NS, "date")).map(|date| date.to_string()),
+        text,
+    }
+}
@@ -0,0 +1,265 @@
+//! Conversion to reStructuredText, e.g. for Sphinx.
+
+use crate::utils::{link_target, link_text};
+use crate::{
+    MarkdownContent, MarkdownDocument, MarkdownOptions, MarkdownParagraph, MarkdownTable,
+    ParagraphStyle, SmartArtNode, TextType,
+};
+
+/// The characters that underline headings, by level. reST only requires that each level is
+/// adorned consistently.
+const HEADING_ADORNMENTS: [char; 6] = ['=', '-', '~', '^', '"', '\''];
+
+impl MarkdownDocument {
+    /// Convert the document to reStructuredText: headings are underlined, lists use `-` and `#.`,
+    /// tables are grid tables, and images are `.. image::` directives. Formatting that reST has
+    /// no markup for, such as underline and strike-through, is left out.
+    pub fn to_rst(&self, options: &MarkdownOptions) -> String {
+        let mut blocks: Vec<String> = vec![];
+        if let Some(title) = &self.title {
+            let adornment = "=".repeat(title.chars().count());
+            blocks.push(format!("{adornment}\n{title}\n{adornment}"));
+        }
+
+        // The indent and content offset of the last list item at each level.
+        let mut list_items: Vec<(usize, usize)> = vec![];
+        for content in &self.content {
+            match content {
+                MarkdownContent::Paragraph(paragraph) => {
+                    if paragraph.is_excluded(options) {
+                        continue;
+                    }
+                    let style = paragraph.resolved_style(&self.styles);
+                    let (text, images) = self.paragraph_rst(paragraph, &style);
+                    let list_item = style
+                        .numbering
+                        .as_ref()
+                        .and_then(|numbering| Some((numbering.id?, numbering.indent_level)));
+                    match (self.paragraph_heading_level(paragraph, options), list_item) {
+                        (Some(level), _) if !text.is_empty() => {
+                            list_items.clear();
+                            let adornment = HEADING_ADORNMENTS[level.clamp(0, 5) as usize]
+                                .to_string()
+                                .repeat(text.chars().count());
+                            blocks.push(format!("{text}\n{adornment}"));
+                        }
+                        (None, Some((id, level))) => {
+                            let level = level.unwrap_or(0).max(0) as usize;
+                            list_items.truncate(level);
+                            let indent = list_items.last().map_or(0, |&(_, offset)| offset);
+                            let marker = match self.numberings.get(&id) {
+                                Some(numbering)
+                                    if numbering.format.as_deref() == Some("bullet") =>
+                                {
+                                    "-"
+                                }
+                                _ => "#.",
+                            };
+                            list_items.push((indent, indent + marker.len() + 1));
+                            blocks.push(format!("{}{marker} {text}", " ".repeat(indent)));
+                        }
+                        _ => {
+                            list_items.clear();
+                            if !text.is_empty() {
+                                blocks.push(text);
+                            }
+                        }
+                    }
+                    blocks.extend(images);
+                }
+                MarkdownContent::Table(table) => {
+                    list_items.clear();
+                    blocks.push(self.grid_table(table));
+                }
+                MarkdownContent::Chart(chart) => {
+                    list_items.clear();
+                    let name = chart.title.as_ref().unwrap_or(&chart.chart_type);
+                    blocks.push(format!("*Chart: {}*", escape(name)));
+                    blocks.push(self.grid_table(&chart.table));
+                }
+                MarkdownContent::SmartArt(smart_art) => {
+                    list_items.clear();
+                    push_smart_art(&mut blocks, &smart_art.nodes, 0);
+                }
+            }
+        }
+
+        for note in &self.notes {
+            let paragraphs: Vec<String> = note
+                .paragraphs
+                .iter()
+                .map(|paragraph| {
+                    let style = paragraph.resolved_style(&self.styles);
+                    self.paragraph_rst(paragraph, &style).0
+                })
+                .collect();
+            blocks.push(format!(
+                ".. [#{}] {}",
+                note.label,
+                paragraphs.join("\n\n   ")
+            ));
+        }
+
+        let mut rst = blocks.join("\n\n");
+        if !rst.is_empty() {
+            rst.push('\n');
+        }
+        rst
+    }
+
+    /// The inline text of a paragraph, and the image directives of its images, which reST can't
+    /// put inline without a substitution.
+    fn paragraph_rst(
+        &self,
+        paragraph: &MarkdownParagraph,
+        style: &ParagraphStyle,
+    ) -> (String, Vec<String>) {
+        let mut text = String::new();
+        let mut images = vec![];
+        // Inline markup has to end before a word character, which an escaped space does.
+        let mut after_markup = false;
+        for block in &paragraph.blocks {
+            let (inline, is_markup) = match block.text_type {
+                TextType::Image => {
+                    images.push(format!(
+                        ".. image:: {}\n   :alt: {}",
+                        link_target(&block.text),
+                        link_text(&block.text)
+                    ));
+                    continue;
+                }
+                TextType::Link => (
+                    format!(
+                        "`{} <{}>`__",
+                        escape(link_text(&block.text)),
+                        link_target(&block.text)
+                    ),
+                    true,
+                ),
+                TextType::Math => (format!(":math:`{}`", block.text), true),
+                TextType::MathBlock => {
+                    images.push(format!(".. math::\n\n   {}", block.text));
+                    continue;
+                }
+                TextType::NoteReference => (format!("[#{}]_", block.text), true),
+                TextType::BookmarkLink | TextType::CommentReference => continue,
+                TextType::ClearBreak | TextType::LineBreak => (" ".to_string(), false),
+                _ => {
+                    let block_style = block.resolved_style(style);
+                    // reST can't nest inline markup, so bold wins over italics.
+                    if block.text.trim().is_empty() {
+                        (block.text.clone(), false)
+                    } else if block_style.code {
+                        (format!("``{}``", block.text), true)
+                    } else if block_style.bold {
+                        (format!("**{}**", escape(&block.text)), true)
+                    } else if block_style.italics {
+                        (format!("*{}*", escape(&block.text)), true)
+                    } else {
+                        (escape(&block.text), false)
+                    }
+                }
+            };
+            let starts_word = inline.starts_with(|c: char| c.is_alphanumeric());
+            let ends_word = text.ends_with(|c: char| c.is_alphanumeric());
+            if (is_markup && ends_word) || (after_markup && starts_word) {
+                text += "\\ ";
+            }
+            text += &inline;
+            after_markup = is_markup;
+        }
+        (text.trim().to_string(), images)
+    }
+
+    /// A grid table, with the cell paragraphs joined by a space.
+    fn grid_table(&self, table: &MarkdownTable) -> String {
+        let columns = table.iter().map(|row| row.columns()).max().unwrap_or(0);
+        let rows: Vec<(bool, Vec<String>)> = table
+            .iter()
+            .map(|row| {
+                let grid_cells = row.grid_cells();
+                let cells = (0..columns)
+                    .map(|c| {
+                        let texts: Vec<String> = grid_cells
+                            .get(c)
+                            .into_iter()
+                            .flat_map(|cell| &cell.paragraphs)
+                            .map(|paragraph| {
+                                let style = paragraph.resolved_style(&self.styles);
+                                self.paragraph_rst(paragraph, &style).0
+                            })
+                            .collect();
+                        texts.join(" ")
+                    })
+                    .collect();
+                (row.is_header, cells)
+            })
+            .collect();
+        let widths: Vec<usize> = (0..columns)
+            .map(|c| {
+                rows.iter()
+                    .map(|(_, cells)| cells[c].chars().count())
+                    .max()
+                    .unwrap_or(0)
+                    .max(1)
+            })
+            .collect();
+        let border = |line: char| {
+            let cells: Vec<String> = widths
+                .iter()
+                .map(|width| line.to_string().repeat(width + 2))
+                .collect();
+            format!("+{}+", cells.join("+"))
+        };
+
+        let mut lines = vec![border('-')];
+        for (i, (is_header, cells)) in rows.iter().enumerate() {
+            let cells: Vec<String> = cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| {
+                    format!(" {cell}{} ", " ".repeat(width - cell.chars().count()))
+                })
+                .collect();
+            lines.push(format!("|{}|", cells.join("|")));
+            let is_last_header = *is_header && !rows.get(i + 1).is_some_and(|(next, _)| *next);
+            lines.push(border(if is_last_header { '=' } else { '-' }));
+        }
+        lines.join("\n")
+    }
+}
+
+/// The nodes of a SmartArt diagram as a nested bullet list, each item a block.
+fn push_smart_art(blocks: &mut Vec<String>, nodes: &[SmartArtNode], level: usize) {
+    for node in nodes {
+        blocks.push(format!("{}- {}", "  ".repeat(level), escape(&node.text)));
+        push_smart_art(blocks, &node.children, level + 1);
+    }
+}
+
+/// Escape the characters of text that reST would read as inline markup, e.g. `\*` for `*`. An
+/// underscore is only escaped at the end of a word, where it would make a reference.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        let escape = match c {
+            '\\' | '*' | '`' | '|' => true,
+            '_' => !chars.peek().is_some_and(|next| next.is_alphanumeric()),
+            _ => false,
+        };
+        if escape {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[test]
+fn test_escape() {
+    assert_eq!(
+        escape("a*b* snake_case word_"),
+        "a\\*b\\* snake_case word\\_"
+    );
+}
@@ -0,0 +1,147 @@
+//! SmartArt diagrams, whose text is stored in a separate diagram data part of the package.
+
+use crate::package::{is_element, A_NS};
+use roxmltree::Node;
+use serde::Serialize;
+use std::collections::HashMap;
+
+pub const DGM_NS: &str = "http://schemas.openxmlformats.org/drawingml/2006/diagram";
+
+/// How deep nodes are nested at most, in case the connections of a diagram form a cycle.
+const MAX_DEPTH: usize = 32;
+
+/// A SmartArt diagram, of which only the text of the nodes is kept.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkdownSmartArt {
+    /// The top-level nodes, in order
+    pub nodes: Vec<SmartArtNode>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmartArtNode {
+    pub text: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<SmartArtNode>,
+}
+
+impl MarkdownSmartArt {
+    /// Parse a diagram data part, e.g. `word/diagrams/data1.xml`.
+    pub fn from_xml(xml: &str) -> Option<Self> {
+        let document = roxmltree::Document::parse(xml).ok()?;
+        let root = document.root_element();
+        let points: Vec<Node> = root
+            .descendants()
+            .filter(|node| is_element(node, DGM_NS, "pt"))
+            .collect();
+        let document_id = points
+            .iter()
+            .find(|point| point.attribute("type") == Some("doc"))?
+            .attribute("modelId")?;
+        // Only the points of the default type are nodes, the others are e.g. transitions.
+        let texts: HashMap<&str, String> = points
+            .iter()
+            .filter(|point| matches!(point.attribute("type"), None | Some("node")))
+            .filter_map(|point| Some((point.attribute("modelId")?, point_text(*point))))
+            .collect();
+        let mut children: HashMap<&str, Vec<(usize, &str)>> = HashMap::new();
+        let connections = root
+            .descendants()
+            .filter(|node| is_element(node, DGM_NS, "cxn"))
+            .filter(|cxn| matches!(cxn.attribute("type"), None | Some("parOf")));
+        for cxn in connections {
+            let (Some(source), Some(destination)) =
+                (cxn.attribute("srcId"), cxn.attribute("destId"))
+            else {
+                continue;
+            };
+            let order = cxn
+                .attribute("srcOrd")
+                .and_then(|order| order.parse().ok())
+                .unwrap_or(0);
+            children
+                .entry(source)
+                .or_default()
+                .push((order, destination));
+        }
+        children.values_mut().for_each(|ids| ids.sort());
+
+        let nodes = child_nodes(document_id, &texts, &children, 0);
+        (!nodes.is_empty()).then_some(MarkdownSmartArt { nodes })
+    }
+
+    /// The nodes as a nested list, using the bullet markers per level like lists do.
+    pub fn to_markdown(&self, bullet_markers: &[String]) -> String {
+        let mut markdown = String::new();
+        push_list(&mut markdown, &self.nodes, bullet_markers, 0);
+        markdown
+    }
+
+    /// The text of the nodes, one per line.
+    pub fn to_plain_text(&self) -> Vec<String> {
+        let mut lines = vec![];
+        push_lines(&mut lines, &self.nodes);
+        lines
+    }
+}
+
+/// The children of the point `id` that have text, or whose children have.
+fn child_nodes(
+    id: &str,
+    texts: &HashMap<&str, String>,
+    children: &HashMap<&str, Vec<(usize, &str)>>,
+    depth: usize,
+) -> Vec<SmartArtNode> {
+    if depth == MAX_DEPTH {
+        return vec![];
+    }
+    children
+        .get(id)
+        .into_iter()
+        .flatten()
+        .filter_map(|(_, child)| {
+            let text = texts.get(child)?;
+            let node = SmartArtNode {
+                text: text.clone(),
+                children: child_nodes(child, texts, children, depth + 1),
+            };
+            (!node.text.is_empty() || !node.children.is_empty()).then_some(node)
+        })
+        .collect()
+}
+
+/// The text of a point, with its paragraphs separated by a space.
+fn point_text(point: Node) -> String {
+    let paragraphs: Vec<String> = point
+        .descendants()
+        .filter(|node| is_element(node, A_NS, "p"))
+        .map(|paragraph| {
+            paragraph
+                .descendants()
+                .filter(|node| is_element(node, A_NS, "t"))
+                .filter_map(|node| node.text())
+                .collect::<String>()
+        })
+        .filter(|text| !text.trim().is_empty())
+        .collect();
+    paragraphs.join(" ")
+}
+
+fn push_list(markdown: &mut String, nodes: &[SmartArtNode], markers: &[String], level: usize) {
+    let marker = match markers {
+        [] => "-",
+        _ => &markers[level % markers.len()],
+    };
+    for node in nodes {
+        *markdown += &format!("{}{marker} {}\n", "    ".repeat(level), node.text);
+        push_list(markdown, &node.children, markers, level + 1);
+    }
+}
+
+fn push_lines(lines: &mut Vec<String>, nodes: &[SmartArtNode]) {
+    for node in nodes {
+        lines.push(node.text.clone());
+        push_lines(lines, &node.children);
+    }
+}
@@ -0,0 +1,240 @@
+//! A fast path that only extracts the text of the document body, see
+//! [`MarkdownDocument::extract_text`](crate::MarkdownDocument::extract_text).
+//!
+//! `word/document.xml` is scanned tag by tag instead of being parsed into a tree, and the styles,
+//! numbering and media of the package are never read.
+
+use crate::package::{DC_NS, W_NS};
+use crate::DocxParseError;
+use std::io::{Read, Seek};
+use zip::result::ZipError;
+use zip::ZipArchive;
+
+/// The text of the document title and body, laid out like
+/// [`MarkdownDocument::to_plain_text`](crate::MarkdownDocument::to_plain_text): a line per
+/// paragraph and per table row, with the cells of a row separated by a tab.
+pub fn extract_text<R: Read + Seek>(reader: R) -> Result<String, DocxParseError> {
+    let mut archive = ZipArchive::new(reader)?;
+    let mut text = String::new();
+    if let Some(title) = title(&mut archive)? {
+        text.push_str(&title);
+    }
+    let mut xml = String::new();
+    match archive.by_name("word/document.xml") {
+        Ok(mut part) => part.read_to_string(&mut xml)?,
+        Err(ZipError::FileNotFound) => {
+            return Err(DocxParseError::MissingPart("word/document.xml".to_string()))
+        }
+        Err(error) => return Err(error.into()),
+    };
+    body_text(&xml, &mut text)?;
+    Ok(text)
+}
+
+/// The title of the core properties, if any.
+fn title<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Result<Option<String>, DocxParseError> {
+    let mut xml = String::new();
+    match archive.by_name("docProps/core.xml") {
+        Ok(mut part) => part.read_to_string(&mut xml)?,
+        Err(ZipError::FileNotFound) => return Ok(None),
+        Err(error) => return Err(error.into()),
+    };
+    let document =
+        roxmltree::Document::parse(&xml).map_err(|error| DocxParseError::Xml(error.to_string()))?;
+    let title = document
+        .root_element()
+        .children()
+        .find(|node| node.tag_name().namespace() == Some(DC_NS) && node.has_tag_name("title"))
+        .and_then(|node| node.text())
+        .filter(|title| !title.is_empty());
+    Ok(title.map(|title| title.to_string()))
+}
+
+/// Append the text of the body to `text`, each paragraph or table row on a new line.
+fn body_text(xml: &str, text: &mut String) -> Result<(), DocxParseError> {
+    let prefix = namespace_prefix(xml, W_NS).unwrap_or("w");
+    let mut lines = usize::from(!text.is_empty());
+    let mut push_line = |text: &mut String, line: &str| {
+        if lines > 0 {
+            text.push('\n');
+        }
+        text.push_str(line);
+        lines += 1;
+    };
+
+    // The buffers are reused for every paragraph, cell and row.
+    let mut paragraph = String::new();
+    let mut cell = String::new();
+    let mut row = String::new();
+    let mut cell_paragraphs = 0;
+    let mut row_cells = 0;
+    let mut paragraph_depth = 0;
+    let mut table_depth = 0;
+    // Tab stops (`w:tabs/w:tab`) are properties, not tabs.
+    let mut in_tab_stops = false;
+    // The content of `mc:Fallback` repeats that of `mc:Choice`.
+    let mut fallback_depth = 0;
+    let mut in_text = false;
+    // A page or column break in the text, which starts a new line if more text follows.
+    let mut after_break = false;
+
+    let mut rest = xml;
+    while let Some(start) = rest.find('<') {
+        if in_text && fallback_depth == 0 {
+            push_unescaped(&mut paragraph, &rest[..start]);
+        }
+        rest = &rest[start..];
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            let end = comment
+                .find("-->")
+                .ok_or_else(|| DocxParseError::Xml("unclosed comment".to_string()))?;
+            rest = &comment[end + 3..];
+            continue;
+        }
+        let end = rest
+            .find('>')
+            .ok_or_else(|| DocxParseError::Xml("unclosed tag".to_string()))?;
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+        if tag.starts_with('?') || tag.starts_with('!') {
+            continue;
+        }
+
+        let is_end = tag.starts_with('/');
+        let is_empty = tag.ends_with('/');
+        let tag = tag.trim_start_matches('/').trim_end_matches('/');
+        let name = tag.split_whitespace().next().unwrap_or_default();
+        if name.ends_with(":Fallback") {
+            match (is_end, is_empty) {
+                (true, _) => fallback_depth -= 1,
+                (false, false) => fallback_depth += 1,
+                (false, true) => (),
+            }
+            continue;
+        }
+        let Some(name) = name
+            .strip_prefix(prefix)
+            .and_then(|name| name.strip_prefix(':'))
+        else {
+            continue;
+        };
+        if fallback_depth > 0 {
+            continue;
+        }
+
+        match (name, is_end) {
+            ("t", _) => {
+                in_text = !is_end && !is_empty;
+                if in_text && std::mem::take(&mut after_break) {
+                    paragraph.push('\n');
+                }
+            }
+            ("tabs", _) => in_tab_stops = !is_end && !is_empty,
+            // Like the full parser, a tab before the text is left out.
+            ("tab", false) if !in_tab_stops && !paragraph.is_empty() => paragraph.push('\t'),
+            ("br", false) => {
+                let is_line_break =
+                    !tag.contains("type=\"page\"") && !tag.contains("type=\"column\"");
+                if is_line_break || tag.contains("clear=\"all\"") {
+                    paragraph.push('\n');
+                } else {
+                    after_break = !paragraph.is_empty();
+                }
+            }
+            ("p", false) if !is_empty => paragraph_depth += 1,
+            ("p", _) => {
+                // An empty paragraph (`<w:p/>`) is opened and closed at once.
+                if !is_empty {
+                    paragraph_depth -= 1;
+                }
+                after_break = false;
+                if paragraph_depth > 0 {
+                    // The end of a paragraph in a text box, inside another paragraph.
+                    paragraph.push(' ');
+                    continue;
+                }
+                if table_depth > 0 {
+                    if cell_paragraphs > 0 {
+                        cell.push(' ');
+                    }
+                    cell.push_str(&paragraph);
+                    cell_paragraphs += 1;
+                } else {
+                    push_line(text, &paragraph);
+                }
+                paragraph.clear();
+            }
+            ("tbl", false) if !is_empty => table_depth += 1,
+            ("tbl", true) => table_depth -= 1,
+            ("tr", false) if table_depth == 1 => {
+                row.clear();
+                row_cells = 0;
+            }
+            ("tr", true) if table_depth == 1 => push_line(text, &row),
+            ("tc", false) if table_depth == 1 => {
+                cell.clear();
+                cell_paragraphs = 0;
+            }
+            ("tc", true) if table_depth == 1 => {
+                if row_cells > 0 {
+                    row.push('\t');
+                }
+                row.push_str(&cell);
+                row_cells += 1;
+            }
+            _ => (),
+        }
+    }
+    Ok(())
+}
+
+/// The prefix that the root element declares for a namespace, e.g. `w` for the main namespace.
+fn namespace_prefix<'a>(xml: &'a str, namespace: &str) -> Option<&'a str> {
+    let declaration = format!("=\"{namespace}\"");
+    let end = xml.find(&declaration)?;
+    let start = xml[..end].rfind("xmlns:")? + "xmlns:".len();
+    Some(&xml[start..end])
+}
+
+/// Append text with the predefined and numeric character references of XML replaced.
+fn push_unescaped(text: &mut String, escaped: &str) {
+    let mut rest = escaped;
+    while let Some(start) = rest.find('&') {
+        text.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let c = match &rest[1..end] {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            reference => reference
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| reference.strip_prefix('#').map(|decimal| decimal.parse()))
+                .and_then(|code| code.ok())
+                .and_then(char::from_u32),
+        };
+        match c {
+            Some(c) => {
+                text.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                text.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    text.push_str(rest);
+}
+
+#[test]
+fn test_push_unescaped() {
+    let mut text = String::new();
+    push_unescaped(&mut text, "Tom &amp; Jerry &lt;3 &#233;t&#xE9; &bogus; &");
+    assert_eq!(text, "Tom & Jerry <3 été &bogus; &");
+}
@@ -9,6 +9,55 @@ use std::{
     path::PathBuf,
 };
 
+/// Wrap text in Unicode bidi isolates, a right-to-left isolate (U+2067) or a left-to-right one
+/// (U+2066), closed by a pop directional isolate (U+2069).
+pub fn bidi_isolate(text: &str, rtl: bool) -> String {
+    let isolate = if rtl { '\u{2067}' } else { '\u{2066}' };
+    format!("{isolate}{text}\u{2069}")
+}
+
+/// Escape the characters of text that markdown would read as emphasis or code, e.g. `a\_b` for
+/// `a_b`. A backslash is escaped when it would escape the character after it.
+pub fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        let escape = match c {
+            '*' | '_' | '`' => true,
+            '\\' => chars.peek().is_some_and(|next| next.is_ascii_punctuation()),
+            _ => false,
+        };
+        if escape {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// An anchor name for text, e.g. `figure-2` for `Figure 2`: lowercase letters and digits, with
+/// a dash for each run of other characters.
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+/// A CSV field, quoted when it contains a comma, quote or line break (RFC 4180).
+pub fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
 pub fn max_lengths_per_column(
     table_with_simple_cells: &Vec<(bool, Vec<String>)>,
     min_width: usize,
@@ -55,7 +104,7 @@ pub fn pad_left(s: &str, width: &usize) -> String {
     padded
 }
 
-pub fn table_row_to_markdown(column_lengths: &Vec<usize>, row: &Vec<String>) -> String {
+pub fn table_row_to_markdown(column_lengths: &[usize], row: &[String]) -> String {
     let mut table_row_in_markdown = "".to_string();
     column_lengths.iter().enumerate().for_each(|(j, width)| {
         let cell = if j < row.len() { &row[j] } else { "" };
@@ -91,6 +140,405 @@ fn test_table_row_to_markdown() {
     );
 }
 
+/// A number as lowercase roman numerals, e.g. `xiv` for 14.
+pub fn to_roman(mut n: usize) -> String {
+    const NUMERALS: [(usize, &str); 13] = [
+        (1000, "m"),
+        (900, "cm"),
+        (500, "d"),
+        (400, "cd"),
+        (100, "c"),
+        (90, "xc"),
+        (50, "l"),
+        (40, "xl"),
+        (10, "x"),
+        (9, "ix"),
+        (5, "v"),
+        (4, "iv"),
+        (1, "i"),
+    ];
+    let mut roman = String::new();
+    for (value, numeral) in NUMERALS {
+        while n >= value {
+            roman += numeral;
+            n -= value;
+        }
+    }
+    roman
+}
+
+/// A number as lowercase letters like spreadsheet columns: `a` to `z`, then `aa`, `ab`, etc.
+pub fn to_letters(mut n: usize) -> String {
+    let mut letters = vec![];
+    while n > 0 {
+        letters.push((b'a' + ((n - 1) % 26) as u8) as char);
+        n = (n - 1) / 26;
+    }
+    letters.iter().rev().collect()
+}
+
+#[test]
+fn test_roman_and_letters() {
+    assert_eq!(to_roman(1), "i");
+    assert_eq!(to_roman(4), "iv");
+    assert_eq!(to_roman(14), "xiv");
+    assert_eq!(to_roman(1994), "mcmxciv");
+    assert_eq!(to_letters(1), "a");
+    assert_eq!(to_letters(26), "z");
+    assert_eq!(to_letters(28), "ab");
+    assert_eq!(to_letters(30), "ad");
+    assert_eq!(to_letters(52), "az");
+    assert_eq!(to_letters(703), "aaa");
+}
+
+/// Check whether a hyperlink target refers to a local or network file rather than a web page,
+/// e.g. `file:///C:/report.docx`, `C:\report.docx` or `\\server\share\doc.docx`. A relative
+/// path such as `../other.docx` only counts when its relationship is external, i.e. it has
+/// `TargetMode="External"`, as relative targets are otherwise parts of the package or anchors.
+pub fn is_file_link(target: &str, external: bool) -> bool {
+    let lower = target.to_lowercase();
+    let mut chars = lower.chars();
+    let drive_letter = matches!(
+        (chars.next(), chars.next(), chars.next()),
+        (Some('a'..='z'), Some(':'), Some('\\' | '/'))
+    );
+    if lower.starts_with("file:") || drive_letter || lower.starts_with("\\\\") {
+        return true;
+    }
+    // Web, mail and other links have a scheme.
+    let has_scheme = match lower.split_once(':') {
+        Some((scheme, _)) => scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c)),
+        None => false,
+    };
+    external && !has_scheme && !lower.starts_with('#')
+}
+
+#[test]
+fn test_is_file_link() {
+    assert!(is_file_link("file:///C:/Users/jane/report.docx", true));
+    assert!(is_file_link("../other.docx", true));
+    assert!(is_file_link("C:\\docs\\report.docx", false));
+    assert!(is_file_link("\\\\server\\share\\doc.docx", false));
+    assert!(!is_file_link("../other.docx", false));
+    assert!(!is_file_link("page.html", false));
+    assert!(!is_file_link("https://example.com", true));
+    assert!(!is_file_link("mailto:jane@example.com", true));
+    assert!(!is_file_link("#bookmark", true));
+}
+
+/// The heading level (0 for `#`) of a heading style id, e.g. 1 for `Heading2`, including the ids
+/// of localized built-in styles, e.g. `berschrift2` in German (Word leaves out the `Ü`) or
+/// `Titre2` in French.
+pub fn heading_style_level(style_id: &str) -> Option<isize> {
+    let digits = style_id.trim_start_matches(|c: char| !c.is_ascii_digit());
+    let name = style_id[..style_id.len() - digits.len()]
+        .trim()
+        .to_lowercase();
+    let is_heading = [
+        "heading",
+        "berschrift",
+        "überschrift",
+        "titre",
+        "titolo",
+        "ttulo",
+        "título",
+        "kop",
+        "rubrik",
+        "overskrift",
+        "otsikko",
+    ]
+    .contains(&name.as_str());
+    match digits.parse::<isize>() {
+        Ok(level @ 1..=9) if is_heading => Some(level - 1),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_heading_style_level() {
+    assert_eq!(heading_style_level("Heading1"), Some(0));
+    assert_eq!(heading_style_level("heading 2"), Some(1));
+    assert_eq!(heading_style_level("berschrift3"), Some(2));
+    assert_eq!(heading_style_level("Titre1"), Some(0));
+    assert_eq!(heading_style_level("Heading"), None);
+    assert_eq!(heading_style_level("Heading10"), None);
+    assert_eq!(heading_style_level("TOC1"), None);
+    assert_eq!(heading_style_level("Heading1Char"), None);
+}
+
+/// The text of a markdown link or image, e.g. `Example` for `[Example](https://example.com)`.
+pub fn link_text(markdown: &str) -> &str {
+    let link = markdown.strip_prefix('!').unwrap_or(markdown);
+    link.strip_prefix('[')
+        .and_then(|link| link.rsplit_once("]("))
+        .map(|(text, _)| text)
+        .unwrap_or(markdown)
+}
+
+/// The target of a markdown link or image, e.g. `https://example.com` for
+/// `[Example](https://example.com)`.
+pub fn link_target(markdown: &str) -> &str {
+    markdown
+        .rsplit_once("](")
+        .and_then(|(_, target)| target.strip_suffix(')'))
+        .unwrap_or_default()
+}
+
+#[test]
+fn test_link_text() {
+    assert_eq!(link_text("[Example](https://example.com)"), "Example");
+    assert_eq!(link_text("![A logo](./media/image1.png)"), "A logo");
+    assert_eq!(link_text("plain"), "plain");
+    assert_eq!(
+        link_target("[Example](https://example.com)"),
+        "https://example.com"
+    );
+    assert_eq!(
+        link_target("![A logo](./media/image1.png)"),
+        "./media/image1.png"
+    );
+}
+
+/// The glyph of a bullet list level text, in the font of the list level if known. Bullets in the
+/// Symbol and Wingdings fonts use code points in the private use area (U+F000 + the character
+/// code) or plain character codes, which are mapped to their Unicode equivalents.
+pub fn bullet_glyph(level_text: &str, font: Option<&str>) -> String {
+    level_text
+        .trim()
+        .chars()
+        .map(|c| match font.and_then(|font| symbol_char(font, c)) {
+            Some(glyph) => glyph,
+            None => match c as u32 {
+                0xF0B7 => '•',
+                0xF0A7 => '▪',
+                0xF0A8 => '◦',
+                0xF06E => '■',
+                0xF0FC => '✓',
+                0xF0D8 => '➢',
+                0xF0E0 => '→',
+                0xF076 => '❖',
+                0xF0B2 => '◊',
+                _ => c,
+            },
+        })
+        .collect()
+}
+
+/// The Unicode character shown for a character of a symbol font, e.g. `ü` in Wingdings is `✓`.
+/// Symbol fonts are also addressed in the private use area, e.g. U+F0FC for `ü`. Returns `None` for
+/// other fonts and unknown characters.
+pub fn symbol_char(font: &str, c: char) -> Option<char> {
+    let code = match c as u32 {
+        code @ 0xF020..=0xF0FF => code - 0xF000,
+        code @ 0x20..=0xFF => code,
+        _ => return None,
+    };
+    let glyph = match (font.to_ascii_lowercase().as_str(), code) {
+        ("symbol", 0xA7) => '♣',
+        ("symbol", 0xA8) => '♦',
+        ("symbol", 0xA9) => '♥',
+        ("symbol", 0xAA) => '♠',
+        ("symbol", 0xAE) => '→',
+        ("symbol", 0xB7) => '•',
+        ("symbol", 0xDE) => '⇒',
+        ("symbol", 0xE0) => '◊',
+        ("wingdings", 0x6C) => '●',
+        ("wingdings", 0x6E) => '■',
+        ("wingdings", 0x71) => '❑',
+        ("wingdings", 0x75) => '◆',
+        ("wingdings", 0x76) => '❖',
+        ("wingdings", 0x9F) => '•',
+        ("wingdings", 0xA7) => '▪',
+        ("wingdings", 0xA8) => '◦',
+        ("wingdings", 0xD8) => '➢',
+        ("wingdings", 0xE0) => '→',
+        ("wingdings", 0xFB) => '✗',
+        ("wingdings", 0xFC) => '✓',
+        ("wingdings", 0xFD) => '☒',
+        ("wingdings", 0xFE) => '☑',
+        _ => return None,
+    };
+    Some(glyph)
+}
+
+#[test]
+fn test_bullet_glyph() {
+    assert_eq!(bullet_glyph("\u{F0B7}", None), "•");
+    assert_eq!(bullet_glyph("\u{F0E0}", None), "→");
+    assert_eq!(bullet_glyph("→", None), "→");
+    assert_eq!(bullet_glyph("o", None), "o");
+    assert_eq!(bullet_glyph("ü", Some("Wingdings")), "✓");
+    assert_eq!(bullet_glyph("\u{F0A7}", Some("Symbol")), "♣");
+    assert_eq!(bullet_glyph("o", Some("Courier New")), "o");
+}
+
+/// Check whether a run is inline code: text in a monospace font, on a light gray shading.
+pub fn is_inline_code(font: Option<&str>, shading: Option<&str>) -> bool {
+    let is_monospace = font.is_some_and(|font| {
+        let font = font.to_ascii_lowercase();
+        [
+            "courier",
+            "consolas",
+            "menlo",
+            "monaco",
+            "lucida console",
+            "mono",
+            "code",
+        ]
+        .iter()
+        .any(|name| font.contains(name))
+    });
+    let is_light_gray = shading
+        .filter(|shading| shading.len() == 6)
+        .and_then(|shading| {
+            let channel = |i: usize| u8::from_str_radix(shading.get(i..i + 2)?, 16).ok();
+            Some([channel(0)?, channel(2)?, channel(4)?])
+        })
+        .is_some_and(|rgb| {
+            let (min, max) = (*rgb.iter().min().unwrap(), *rgb.iter().max().unwrap());
+            min >= 0xC0 && max - min <= 0x10 && min < 0xFF
+        });
+    is_monospace && is_light_gray
+}
+
+#[test]
+fn test_is_inline_code() {
+    assert!(is_inline_code(Some("Consolas"), Some("F2F2F2")));
+    assert!(is_inline_code(Some("Courier New"), Some("d9d9d9")));
+    assert!(!is_inline_code(Some("Courier New"), None));
+    assert!(!is_inline_code(Some("Courier New"), Some("FFFFFF")));
+    assert!(!is_inline_code(Some("Courier New"), Some("FFFF00")));
+    assert!(!is_inline_code(Some("Arial"), Some("F2F2F2")));
+}
+
+/// The RGB color of a highlight (`w:highlight`), e.g. `FFFF00` for `yellow`. Returns `None` for
+/// `none` and unknown names.
+pub fn highlight_rgb(highlight: &str) -> Option<&'static str> {
+    let rgb = match highlight {
+        "black" => "000000",
+        "blue" => "0000FF",
+        "cyan" => "00FFFF",
+        "green" => "00FF00",
+        "magenta" => "FF00FF",
+        "red" => "FF0000",
+        "yellow" => "FFFF00",
+        "white" => "FFFFFF",
+        "darkBlue" => "000080",
+        "darkCyan" => "008080",
+        "darkGreen" => "008000",
+        "darkMagenta" => "800080",
+        "darkRed" => "800000",
+        "darkYellow" => "808000",
+        "darkGray" => "808080",
+        "lightGray" => "C0C0C0",
+        _ => return None,
+    };
+    Some(rgb)
+}
+
+/// Hard-wrap a line of markdown at `width` columns. Continuation lines are indented by `indent`
+/// spaces, e.g. to line up with the text of a list item. Links, images, inline code and
+/// equations are never split, so a line may exceed the width when a word doesn't fit.
+pub fn wrap_markdown(line: &str, width: usize, indent: usize) -> String {
+    let mut words: Vec<String> = vec![];
+    let mut word = String::new();
+    let (mut brackets, mut in_target, mut in_code, mut in_math) = (0, false, false, false);
+    let mut previous = None;
+    for c in line.chars() {
+        match c {
+            ' ' if brackets == 0 && !in_target && !in_code && !in_math => {
+                words.push(std::mem::take(&mut word));
+                previous = Some(c);
+                continue;
+            }
+            '`' => in_code = !in_code,
+            '$' if !in_code => in_math = !in_math,
+            '[' if !in_code && !in_math => brackets += 1,
+            ']' if !in_code && !in_math && brackets > 0 => brackets -= 1,
+            '(' if previous == Some(']') && brackets == 0 => in_target = true,
+            ')' if in_target => in_target = false,
+            _ => (),
+        }
+        word.push(c);
+        previous = Some(c);
+    }
+    words.push(word);
+
+    // Leading spaces are kept as indentation of the first line.
+    let leading = words.iter().take_while(|word| word.is_empty()).count();
+    // A word that would start a block at the start of a line, e.g. `-` or `1.`, stays on the line
+    // of the word before it.
+    let mut units: Vec<String> = vec![];
+    for word in words.into_iter().skip(leading) {
+        match units.last_mut() {
+            Some(unit) if starts_block(&word) => {
+                unit.push(' ');
+                unit.push_str(&word);
+            }
+            _ => units.push(word),
+        }
+    }
+    let mut wrapped = " ".repeat(leading);
+    let mut column = leading;
+    let mut line_start = true;
+    for word in units {
+        let length = word.chars().count();
+        if !line_start && column + 1 + length > width {
+            wrapped.push('\n');
+            wrapped.push_str(&" ".repeat(indent));
+            column = indent;
+            line_start = true;
+        }
+        if !line_start {
+            wrapped.push(' ');
+            column += 1;
+        }
+        wrapped.push_str(&word);
+        column += length;
+        line_start = false;
+    }
+    wrapped
+}
+
+/// Check whether a word at the start of a line would start a markdown block, e.g. a list item,
+/// a heading, a quote, a thematic break or the underline of a setext heading.
+fn starts_block(word: &str) -> bool {
+    let number = word.trim_end_matches(['.', ')']);
+    matches!(word, "-" | "+" | "*")
+        || word.starts_with('>')
+        || word.starts_with("```")
+        || word.starts_with("~~~")
+        || (word.len() <= 6 && !word.is_empty() && word.chars().all(|c| c == '#'))
+        || (!word.is_empty() && (word.chars().all(|c| c == '-') || word.chars().all(|c| c == '=')))
+        || (word.len() >= 3 && (word.chars().all(|c| c == '*') || word.chars().all(|c| c == '_')))
+        || (word.len() == number.len() + 1
+            && (1..=9).contains(&number.len())
+            && number.chars().all(|c| c.is_ascii_digit()))
+}
+
+#[test]
+fn test_wrap_markdown() {
+    assert_eq!(
+        wrap_markdown("one two three four", 9, 0),
+        "one two\nthree\nfour"
+    );
+    assert_eq!(
+        wrap_markdown("see [the long link](https://example.com) now", 10, 0),
+        "see\n[the long link](https://example.com)\nnow"
+    );
+    assert_eq!(
+        wrap_markdown("1. alpha beta gamma", 10, 3),
+        "1. alpha\n   beta\n   gamma"
+    );
+    // No continuation line starts a list item, heading or quote.
+    assert_eq!(
+        wrap_markdown("in the year 1. Then - or # or > it", 12, 0),
+        "in the\nyear 1.\nThen - or #\nor > it"
+    );
+}
+
 pub fn save_image_to_file(path: &str, image_data: &[u8]) -> io::Result<()> {
     // Get the current working directory
     let current_dir = env::current_dir()?;
@@ -116,18 +564,68 @@ pub fn save_image_to_file(path: &str, image_data: &[u8]) -> io::Result<()> {
     Ok(())
 }
 
-fn get_mime_type(filename: &str) -> Option<&'static str> {
-    let extension = filename.split('.').last()?;
+pub fn get_mime_type(filename: &str) -> Option<&'static str> {
+    let extension = filename.rsplit('.').next()?;
     match extension.to_lowercase().as_str() {
         "png" => Some("image/png"),
         "jpg" | "jpeg" => Some("image/jpeg"),
         "gif" => Some("image/gif"),
         "bmp" => Some("image/bmp"),
-        "tiff" => Some("image/tiff"),
+        "tif" | "tiff" => Some("image/tiff"),
+        "svg" => Some("image/svg+xml"),
+        "emf" => Some("image/emf"),
+        "wmf" => Some("image/wmf"),
         _ => None,
     }
 }
 
+/// The mime type of an image from its magic bytes, for media without a known extension.
+pub fn sniff_mime_type(data: &[u8]) -> Option<&'static str> {
+    const SIGNATURES: [(&[u8], &str); 7] = [
+        (b"\x89PNG\r\n\x1a\n", "image/png"),
+        (b"\xff\xd8\xff", "image/jpeg"),
+        (b"GIF87a", "image/gif"),
+        (b"GIF89a", "image/gif"),
+        (b"BM", "image/bmp"),
+        (b"II*\0", "image/tiff"),
+        (b"MM\0*", "image/tiff"),
+    ];
+    SIGNATURES
+        .iter()
+        .find(|(signature, _)| data.starts_with(signature))
+        .map(|(_, mime_type)| *mime_type)
+}
+
+/// The mime type of an image from the extension of its name or, failing that, its data.
+pub fn image_mime_type(name: &str, data: &[u8]) -> Option<&'static str> {
+    get_mime_type(name).or_else(|| sniff_mime_type(data))
+}
+
+#[test]
+fn test_image_mime_type() {
+    assert_eq!(image_mime_type("media/image1.png", b""), Some("image/png"));
+    assert_eq!(
+        image_mime_type("media/image1", b"\xff\xd8\xff\xe0"),
+        Some("image/jpeg")
+    );
+    assert_eq!(
+        image_mime_type("media/image1.bin", b"GIF89a"),
+        Some("image/gif")
+    );
+    assert_eq!(
+        image_mime_type("media/image1", b"II*\0"),
+        Some("image/tiff")
+    );
+    assert_eq!(image_mime_type("media/image1", b"data"), None);
+}
+
+/// A base64 data URL of an image, e.g. `data:image/png;base64,...`, with the mime type from
+/// [`image_mime_type`].
+pub fn data_url(name: &str, data: &[u8]) -> String {
+    let mime_type = image_mime_type(name, data).unwrap_or("application/octet-stream");
+    format!("data:{};base64,{}", mime_type, BASE64_STANDARD.encode(data))
+}
+
 pub fn serialize_images<S>(
     images: &HashMap<String, Vec<u8>>,
     serializer: S,
@@ -137,14 +635,152 @@ where
 {
     let mut map = serializer.serialize_map(Some(images.len()))?;
     for (key, value) in images {
-        let encoded = BASE64_STANDARD.encode(value);
-
-        let prefix = match get_mime_type(key) {
-            Some(mime_type) => format!("data:{};base64,", mime_type),
-            None => "data:application/octet-stream;base64,".to_string(),
-        };
-        let base64_string = format!("{}{}", prefix, encoded);
-        map.serialize_entry(key, &base64_string)?;
+        map.serialize_entry(key, &data_url(key, value))?;
     }
     map.end()
 }
+
+const ONES: [&str; 20] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+const TENS: [&str; 10] = [
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+/// Returns true when the language tag (e.g. `en-US`) is English, or when no language is known.
+fn is_english(lang: Option<&str>) -> bool {
+    match lang {
+        Some(lang) => lang.to_lowercase().starts_with("en"),
+        None => true,
+    }
+}
+
+fn english_cardinal(n: usize) -> String {
+    match n {
+        0..=19 => ONES[n].to_string(),
+        20..=99 => match n % 10 {
+            0 => TENS[n / 10].to_string(),
+            ones => format!("{}-{}", TENS[n / 10], ONES[ones]),
+        },
+        100..=999 => match n % 100 {
+            0 => format!("{} hundred", ONES[n / 100]),
+            rest => format!("{} hundred {}", ONES[n / 100], english_cardinal(rest)),
+        },
+        _ => match n % 1000 {
+            0 => format!("{} thousand", english_cardinal(n / 1000)),
+            rest => format!(
+                "{} thousand {}",
+                english_cardinal(n / 1000),
+                english_cardinal(rest)
+            ),
+        },
+    }
+}
+
+fn english_ordinal(n: usize) -> String {
+    let cardinal = english_cardinal(n);
+    // Only the last word changes, e.g. "twenty-one" becomes "twenty-first".
+    let split_at = cardinal.rfind(['-', ' ']).map(|i| i + 1).unwrap_or(0);
+    let (head, last) = cardinal.split_at(split_at);
+    let last = match last {
+        "one" => "first".to_string(),
+        "two" => "second".to_string(),
+        "three" => "third".to_string(),
+        "five" => "fifth".to_string(),
+        "eight" => "eighth".to_string(),
+        "nine" => "ninth".to_string(),
+        "twelve" => "twelfth".to_string(),
+        word if word.ends_with('y') => format!("{}ieth", &word[..word.len() - 1]),
+        word => format!("{word}th"),
+    };
+    format!("{head}{last}")
+}
+
+/// Spell out a number as Word does for the `cardinalText` and `ordinalText` numbering formats,
+/// e.g. "Three" or "Third". Returns `None` when the language is not supported.
+pub fn number_to_words(n: usize, ordinal: bool, lang: Option<&str>) -> Option<String> {
+    if !is_english(lang) {
+        return None;
+    }
+    let words = if ordinal {
+        english_ordinal(n)
+    } else {
+        english_cardinal(n)
+    };
+    let mut chars = words.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+}
+
+/// The suffix used by the `ordinal` numbering format, e.g. "st" for 1st. Returns `None` when the
+/// language is not supported.
+pub fn ordinal_suffix(n: usize, lang: Option<&str>) -> Option<&'static str> {
+    if !is_english(lang) {
+        return None;
+    }
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    Some(suffix)
+}
+
+#[test]
+fn test_number_to_words() {
+    assert_eq!(
+        number_to_words(1, true, Some("en-US")),
+        Some("First".to_string())
+    );
+    assert_eq!(number_to_words(2, true, None), Some("Second".to_string()));
+    assert_eq!(
+        number_to_words(21, true, None),
+        Some("Twenty-first".to_string())
+    );
+    assert_eq!(
+        number_to_words(40, true, None),
+        Some("Fortieth".to_string())
+    );
+    assert_eq!(
+        number_to_words(112, false, None),
+        Some("One hundred twelve".to_string())
+    );
+    assert_eq!(number_to_words(3, true, Some("nl-NL")), None);
+    assert_eq!(ordinal_suffix(12, None), Some("th"));
+    assert_eq!(ordinal_suffix(22, None), Some("nd"));
+}
+
+#[test]
+fn test_slugify() {
+    assert_eq!(slugify("Figure 2"), "figure-2");
+    assert_eq!(slugify(" Table 1.3 (a) "), "table-1-3-a");
+}
+
+#[test]
+fn test_escape_markdown() {
+    assert_eq!(escape_markdown("a_b_c"), "a\\_b\\_c");
+    assert_eq!(escape_markdown("1*2*3 `x`"), "1\\*2\\*3 \\`x\\`");
+    assert_eq!(escape_markdown("C:\\temp \\*"), "C:\\temp \\\\\\*");
+}